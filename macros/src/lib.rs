@@ -0,0 +1,139 @@
+//! Derive macros of the game crate, they expect its `crate::` paths.
+
+use {
+  proc_macro::TokenStream,
+  proc_macro2::TokenStream as TokenStream2,
+  quote::quote,
+  syn::{Data, DeriveInput, Error, Fields, Index, Member, parse_macro_input},
+};
+
+/// Implements `Component` and `LazyTimer` for a struct with a `#[timer]`
+/// field, along with `new(secs)` filling the other fields by default.
+///
+/// The field takes the mode as `#[timer(once)]` or `#[timer(repeating)]`,
+/// repeating by default, once-timers may be removed after they finish with
+/// `#[timer(once, remove)]`. The tick system is registered once the first
+/// timer is added, so `Component` must not be derived separately.
+#[proc_macro_derive(LazyTimer, attributes(timer))]
+pub fn derive_lazy_timer(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  lazy_timer(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+  Once,
+  Repeating,
+}
+
+fn lazy_timer(input: DeriveInput) -> syn::Result<TokenStream2> {
+  let Data::Struct(data) = &input.data else {
+    return Err(Error::new_spanned(&input, "`LazyTimer` expects a struct"));
+  };
+
+  let mut timer = None;
+  for (index, field) in data.fields.iter().enumerate() {
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("timer"))
+    {
+      if timer.is_some() {
+        return Err(Error::new_spanned(attr, "only one `#[timer]` field"));
+      }
+      let (mut mode, mut remove) = (Mode::Repeating, false);
+      if !matches!(attr.meta, syn::Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| {
+          if meta.path.is_ident("once") {
+            mode = Mode::Once;
+          } else if meta.path.is_ident("repeating") {
+            mode = Mode::Repeating;
+          } else if meta.path.is_ident("remove") {
+            remove = true;
+          } else {
+            return Err(meta.error("expected `once`, `repeating` or `remove`"));
+          }
+          Ok(())
+        })?;
+      }
+      if remove && matches!(mode, Mode::Repeating) {
+        return Err(Error::new_spanned(attr, "only once-timers are removed"));
+      }
+      let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+      };
+      timer = Some((member, mode, remove));
+    }
+  }
+  let Some((member, mode, remove)) = timer else {
+    return Err(Error::new_spanned(&input, "missing a `#[timer]` field"));
+  };
+
+  let ident = &input.ident;
+  let (impl_generics, ty_generics, where_clause) =
+    input.generics.split_for_impl();
+
+  let mode = match mode {
+    Mode::Once => quote!(::bevy::time::TimerMode::Once),
+    Mode::Repeating => quote!(::bevy::time::TimerMode::Repeating),
+  };
+  let timer = quote!(::bevy::time::Timer::from_seconds(secs, #mode));
+  let fields = data.fields.iter().enumerate().map(|(index, field)| {
+    let value = if is_member(&member, field, index) {
+      timer.clone()
+    } else {
+      quote!(::core::default::Default::default())
+    };
+    match &field.ident {
+      Some(ident) => quote!(#ident: #value),
+      None => value,
+    }
+  });
+  let new = match &data.fields {
+    Fields::Named(_) => quote!(Self { #(#fields),* }),
+    Fields::Unnamed(_) => quote!(Self(#(#fields),*)),
+    Fields::Unit => unreachable!("unit structs have no `#[timer]` field"),
+  };
+
+  Ok(quote! {
+    impl #impl_generics #ident #ty_generics #where_clause {
+      pub fn new(secs: f32) -> Self {
+        #new
+      }
+    }
+
+    impl #impl_generics ::bevy::ecs::component::Component
+      for #ident #ty_generics #where_clause
+    {
+      const STORAGE_TYPE: ::bevy::ecs::component::StorageType =
+        ::bevy::ecs::component::StorageType::Table;
+      type Mutability = ::bevy::ecs::component::Mutable;
+
+      fn on_add() -> ::core::option::Option<
+        ::bevy::ecs::lifecycle::ComponentHook,
+      > {
+        ::core::option::Option::Some(crate::core::timer::on_add::<Self>)
+      }
+    }
+
+    impl #impl_generics crate::core::LazyTimer
+      for #ident #ty_generics #where_clause
+    {
+      const MODE: ::bevy::time::TimerMode = #mode;
+      const REMOVE: bool = #remove;
+
+      fn timer(&self) -> &::bevy::time::Timer {
+        &self.#member
+      }
+
+      fn timer_mut(&mut self) -> &mut ::bevy::time::Timer {
+        &mut self.#member
+      }
+    }
+  })
+}
+
+fn is_member(member: &Member, field: &syn::Field, index: usize) -> bool {
+  match member {
+    Member::Named(ident) => field.ident.as_ref() == Some(ident),
+    Member::Unnamed(unnamed) => unnamed.index as usize == index,
+  }
+}
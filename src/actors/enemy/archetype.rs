@@ -0,0 +1,147 @@
+use {
+  super::{Boid, Stats},
+  crate::{actors::item::Item, prelude::*},
+  bevy::asset::{AssetLoader, LoadContext, io::Reader},
+  serde::Deserialize,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Kind>()
+    .init_asset::<Archetype>()
+    .register_asset_loader(ArchetypeLoader);
+
+  app.register_type::<EnemyAssets>();
+  app.configure_loading_state(
+    LoadingStateConfig::new(Game::Loading).load_collection::<EnemyAssets>(),
+  );
+}
+
+/// Archetype of the enemy, mirrors the `Archetype` enum of the LDtk project.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum Kind {
+  #[default]
+  Slime,
+  Bat,
+}
+
+impl Kind {
+  pub fn from_ldtk(instance: &EntityInstance) -> Self {
+    match instance.get_enum_field("archetype").map(String::as_str) {
+      Ok("Slime") => Self::Slime,
+      Ok("Bat") => Self::Bat,
+      value => {
+        warn!("unknown `{}` archetype: {value:?}", instance.identifier);
+        Self::default()
+      }
+    }
+  }
+}
+
+#[derive(AssetCollection, Resource, Reflect)]
+pub struct EnemyAssets {
+  #[asset(path = "enemies/slime.enemy.ron")]
+  pub slime: Handle<Archetype>,
+  #[asset(path = "enemies/bat.enemy.ron")]
+  pub bat: Handle<Archetype>,
+}
+
+impl EnemyAssets {
+  pub fn archetype(&self, kind: Kind) -> &Handle<Archetype> {
+    match kind {
+      Kind::Slime => &self.slime,
+      Kind::Bat => &self.bat,
+    }
+  }
+}
+
+/// Enemy description loaded from `*.enemy.ron` files.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct Archetype {
+  pub stats: Stats,
+  pub collider: Shape,
+  pub look: Look,
+  pub behaviour: Behaviour,
+  #[serde(default)]
+  pub loot: Vec<Item>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum Shape {
+  Circle(f32),
+  Rectangle(f32, f32),
+}
+
+impl From<Shape> for Collider {
+  fn from(shape: Shape) -> Self {
+    match shape {
+      Shape::Circle(radius) => Collider::circle(radius),
+      Shape::Rectangle(width, height) => Collider::rectangle(width, height),
+    }
+  }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Look {
+  pub color: (f32, f32, f32),
+  pub size: (f32, f32),
+  /// path to the sprite image, plain colored rectangle otherwise
+  #[serde(default)]
+  pub image: Option<String>,
+  #[serde(skip)]
+  texture: Option<Handle<Image>>,
+}
+
+impl Look {
+  pub fn sprite(&self) -> Sprite {
+    let (r, g, b) = self.color;
+    let size = Vec2::new(self.size.0, self.size.1);
+
+    match &self.texture {
+      Some(image) => Sprite {
+        image: image.clone(),
+        color: Color::srgb(r, g, b),
+        custom_size: Some(size),
+        ..default()
+      },
+      None => Sprite::from_color(Color::srgb(r, g, b), size),
+    }
+  }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum Behaviour {
+  /// walks on the ground, affected by gravity
+  Walk,
+  /// flies in a flock
+  Fly(Boid),
+}
+
+#[derive(Default, TypePath)]
+struct ArchetypeLoader;
+
+impl AssetLoader for ArchetypeLoader {
+  type Asset = Archetype;
+  type Settings = ();
+  type Error = BevyError;
+
+  async fn load(
+    &self,
+    reader: &mut dyn Reader,
+    _settings: &(),
+    load_context: &mut LoadContext<'_>,
+  ) -> Result<Archetype> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+
+    let mut archetype: Archetype = ron::de::from_bytes(&bytes)?;
+    archetype.look.texture =
+      archetype.look.image.as_ref().map(|path| load_context.load(path));
+    Ok(archetype)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &["enemy.ron"]
+  }
+}
@@ -1,27 +1,210 @@
-use {super::Stats, crate::prelude::*};
+use {
+  super::{Enemy, Stats},
+  crate::{actors::Player, prelude::*},
+  serde::Deserialize,
+};
 
 pub fn plugin(app: &mut App) {
-  app.add_systems(FixedUpdate, boid.in_set(ControllerInputsSet));
+  app
+    .register_type::<Boid>()
+    .register_type::<Flying>()
+    .init_resource::<Grid>()
+    .add_systems(
+      FixedUpdate,
+      (rebuild_grid, boid).chain().in_set(ControllerInputsSet),
+    );
 }
 
-#[derive(Component, Reflect)]
-pub struct Boid(pub f32);
+/// Flying enemy archetype: ignores gravity and moves as a part of a flock.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+#[require(Enemy, Boid, RigidBody::Dynamic, GravityScale(0.0))]
+pub struct Flying;
+
+/// Weights of the boid rules, neighbours are searched within `Stats::vision`.
+#[derive(Component, Reflect, Deserialize, Clone, Copy, Debug)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct Boid {
+  /// steer away from too close neighbours
+  pub separation: f32,
+  /// match the velocity of neighbours
+  pub alignment: f32,
+  /// steer towards the center of neighbours
+  pub cohesion: f32,
+  /// steer towards the visible player
+  pub seek: f32,
+}
+
+impl Default for Boid {
+  fn default() -> Self {
+    Self { separation: 1.5, alignment: 1.0, cohesion: 1.0, seek: 2.0 }
+  }
+}
+
+#[derive(Clone, Copy)]
+struct Agent {
+  entity: Entity,
+  position: Vec2,
+  velocity: Vec2,
+}
+
+/// Uniform spatial hash, avoids `iter_combinations` over the whole flock
+#[derive(Resource, Default)]
+struct Grid {
+  cell: f32,
+  cells: HashMap<IVec2, Vec<Agent>>,
+}
+
+impl Grid {
+  fn key(&self, position: Vec2) -> IVec2 {
+    (position / self.cell).floor().as_ivec2()
+  }
+
+  fn clear(&mut self, cell: f32) {
+    let cell = cell.max(f32::EPSILON);
+    if cell != self.cell {
+      // every key moves with the cell size
+      self.cells.clear();
+      self.cell = cell;
+    }
+    // keep allocations of the occupied cells between ticks, the cells left
+    // behind by the flock are dropped
+    self.cells.retain(|_, agents| {
+      let occupied = !agents.is_empty();
+      agents.clear();
+      occupied
+    });
+  }
+
+  fn insert(&mut self, agent: Agent) {
+    self.cells.entry(self.key(agent.position)).or_default().push(agent);
+  }
+
+  /// All agents within `radius` of `position` (`radius` <= cell size)
+  fn neighbours(
+    &self,
+    position: Vec2,
+    radius: f32,
+  ) -> impl Iterator<Item = &Agent> {
+    let key = self.key(position);
+    (-1..=1)
+      .flat_map(move |y| (-1..=1).map(move |x| key + IVec2::new(x, y)))
+      .filter_map(|key| self.cells.get(&key))
+      .flatten()
+      .filter(move |agent| agent.position.distance(position) < radius)
+  }
+}
+
+fn rebuild_grid(
+  mut grid: ResMut<Grid>,
+  query: Query<(Entity, &Transform2D, &LinearVelocity, &Stats), With<Boid>>,
+) {
+  let cell = query.iter().map(|(.., stats)| stats.vision).fold(0.0, f32::max);
+
+  grid.clear(cell);
+  for (entity, transform, velocity, _) in query.iter() {
+    grid.insert(Agent {
+      entity,
+      position: transform.translation,
+      velocity: velocity.0,
+    });
+  }
+}
 
 fn boid(
-  mut controllers: Query<&mut Controller>,
-  query: Query<(Entity, &Transform2D, &Stats, &Boid)>,
+  grid: Res<Grid>,
+  mut query: Query<(
+    Entity,
+    &Transform2D,
+    &LinearVelocity,
+    &Stats,
+    &Boid,
+    &mut Controller,
+  )>,
+  players: Query<&Transform2D, (With<Player>, Without<Boid>)>,
 ) {
-  for [(entity, a, stats, &Boid(force)), (_, b, _, _)] in
-    query.iter_combinations()
+  for (entity, transform, velocity, stats, boid, mut controller) in
+    query.iter_mut()
   {
-    let movement = a.translation - b.translation;
-    let affect = -(movement.length() - stats.vision) / stats.vision;
+    let position = transform.translation;
+
+    let mut separation = Vec2::ZERO;
+    let mut heading = Vec2::ZERO;
+    let mut center = Vec2::ZERO;
+    let mut count = 0;
+
+    for other in grid
+      .neighbours(position, stats.vision)
+      .filter(|other| other.entity != entity)
+    {
+      let away = position - other.position;
+      // closer neighbours push harder
+      let affect = (stats.vision - away.length()) / stats.vision;
+      separation += away.normalize_or_zero() * affect;
+      heading += other.velocity;
+      center += other.position;
+      count += 1;
+    }
 
-    if affect > 0.0
-      && let Ok(mut controller) = controllers.get_mut(entity)
+    let mut force = separation * boid.separation;
+    if count > 0 {
+      let count = count as f32;
+      force +=
+        (heading / count - velocity.0).normalize_or_zero() * boid.alignment;
+      force += (center / count - position).normalize_or_zero() * boid.cohesion;
+    }
+
+    if let Some(target) = players
+      .iter()
+      .map(|player| player.translation)
+      .filter(|target| target.distance(position) < stats.vision)
+      .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
     {
-      controller
-        .control(Control::Move(movement.normalize_or_zero() * affect * force));
+      force += (target - position).normalize_or_zero() * boid.seek;
     }
+
+    let velocity =
+      (velocity.0 + force * stats.speed).clamp_length_max(stats.speed);
+    controller.control(Control::Move(velocity));
   }
 }
+
+#[test]
+fn neighbours() {
+  let agent = |x: f32, y: f32| Agent {
+    entity: Entity::PLACEHOLDER,
+    position: Vec2::new(x, y),
+    velocity: Vec2::ZERO,
+  };
+
+  let mut grid = Grid::default();
+  grid.clear(10.0);
+  for agent in [agent(0.0, 0.0), agent(9.0, 0.0), agent(-5.0, -5.0)] {
+    grid.insert(agent);
+  }
+  grid.insert(agent(15.0, 0.0));
+
+  assert_eq!(grid.neighbours(Vec2::new(1.0, 0.0), 10.0).count(), 3);
+  assert_eq!(grid.neighbours(Vec2::new(14.0, 0.0), 6.0).count(), 2);
+}
+
+#[test]
+fn drops_left_cells() {
+  let agent = |x: f32| Agent {
+    entity: Entity::PLACEHOLDER,
+    position: Vec2::new(x, 0.0),
+    velocity: Vec2::ZERO,
+  };
+
+  let mut grid = Grid::default();
+  // a boid roaming to the right
+  for x in 0..100 {
+    grid.clear(10.0);
+    grid.insert(agent(x as f32 * 10.0));
+  }
+  assert!(grid.cells.len() <= 2);
+
+  grid.clear(20.0);
+  assert!(grid.cells.is_empty());
+}
@@ -0,0 +1,83 @@
+use {
+  super::{Flying, Stats},
+  crate::{
+    actors::Player,
+    level::nav::{Link, Navigation, Waypoint},
+    prelude::*,
+  },
+};
+
+pub fn plugin(app: &mut App) {
+  app.add_systems(FixedUpdate, chase.in_set(ControllerInputsSet));
+}
+
+/// Path of the walking enemy to the player
+#[derive(Component, Default)]
+pub struct Chase {
+  path: VecDeque<Waypoint>,
+  goal: Option<IVec2>,
+}
+
+fn chase(
+  nav: Navigation,
+  gravity: Res<Gravity>,
+  mut query: Query<
+    (
+      &Transform2D,
+      &LinearVelocity,
+      &KinematicContacts,
+      &Stats,
+      &mut Chase,
+      &mut Controller,
+    ),
+    Without<Flying>,
+  >,
+  players: Query<&Transform2D, (With<Player>, Without<Chase>)>,
+) {
+  for (transform, velocity, contacts, stats, mut chase, mut controller) in
+    query.iter_mut()
+  {
+    let position = transform.translation;
+    let Some(target) = players
+      .iter()
+      .map(|player| player.translation)
+      .find(|target| target.distance(position) < stats.vision)
+    else {
+      chase.path.clear();
+      chase.goal = None;
+      continue;
+    };
+
+    // repath only when the player moves to another cell
+    let goal = nav.locate(target).map(|(.., cell)| cell);
+    if goal != chase.goal || chase.path.is_empty() {
+      chase.goal = goal;
+      chase.path =
+        nav.path(position, target, stats.jump).unwrap_or_default().into();
+    }
+
+    let Some((graph, ..)) = nav.locate(position) else { continue };
+    let grid_size = graph.grid_size;
+
+    while let Some(next) = chase.path.front()
+      && (next.position.x - position.x).abs() < grid_size * 0.25
+      && (next.position.y - position.y).abs() < grid_size
+    {
+      chase.path.pop_front();
+    }
+    let Some(&Waypoint { position: next, link }) = chase.path.front() else {
+      continue;
+    };
+
+    let mut velocity = velocity.0;
+    velocity.x = (next.x - position.x).signum() * stats.speed;
+    if let Link::Jump { height, .. } = link
+      && contacts.grounded
+    {
+      // a bit higher than the ledge to land on it
+      let height = (height as f32 + 0.5) * grid_size;
+      velocity.y = (2.0 * gravity.0.length() * height).sqrt();
+    }
+    controller.control(Control::Move(velocity));
+  }
+}
@@ -0,0 +1,30 @@
+use {
+  super::{Enemy, Kind},
+  crate::{
+    actors::item::{Item, Loot},
+    prelude::*,
+  },
+};
+
+pub fn plugin(app: &mut App) {
+  app.register_ldtk_entity::<MobBundle>("Mob");
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct MobBundle {
+  pub enemy: Enemy,
+  #[with(archetype)]
+  pub kind: Kind,
+  #[with(loot)]
+  pub loot: Loot,
+  #[from_entity_instance]
+  entity_instance: EntityInstance,
+}
+
+fn archetype(instance: &EntityInstance) -> Kind {
+  Kind::from_ldtk(instance)
+}
+
+fn loot(instance: &EntityInstance) -> Loot {
+  Loot(Item::from_field(instance, "loot"))
+}
@@ -1,40 +1,83 @@
+mod archetype;
 mod boid;
+mod chase;
+mod ldtk;
+mod spawner;
 
-use crate::{ prelude::*};
+use {
+  crate::{
+    actors::item::Loot,
+    level::{checkpoint::KILL_DEPTH, ldtk::Levels, nav::Jump},
+    prelude::*,
+    save::Progress,
+  },
+  serde::Deserialize,
+};
+
+pub use {
+  archetype::{Archetype, Behaviour, EnemyAssets, Kind},
+  boid::{Boid, Flying},
+  chase::Chase,
+  spawner::Spawner,
+};
 
 pub fn plugin(app: &mut App) {
+  app.register_type::<Enemy>().register_type::<Stats>();
   app.add_plugins((
-    // boid::plugin,
+    archetype::plugin,
+    boid::plugin,
+    chase::plugin,
+    ldtk::plugin,
+    spawner::plugin,
   ));
-  app.add_systems(Update, spawn.in_set(Systems::Spawn));
+  app
+    .add_systems(
+      Update,
+      (
+        spawn.in_set(Systems::Spawn).run_if(resource_exists::<EnemyAssets>),
+        fall.in_set(Systems::Update),
+      ),
+    )
+    .add_observer(kill);
 }
 
-#[derive(Component, Reflect)]
-#[require(Stats)]
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+#[require(Stats, Kind)]
 pub struct Enemy;
 
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Deserialize, Clone, Debug)]
+#[reflect(Component)]
+#[serde(default)]
 pub struct Stats {
   pub vision: f32,
   pub speed: f32,
   pub attack: Attack,
   pub patrol: Patrol,
+  pub jump: Jump,
 }
 
 impl Default for Stats {
   fn default() -> Self {
-    // todo!> use config loading, to avoid `tilemap::METER` mul
+    // fallback for enemies without loaded archetype
     Self {
       speed: 2.0,
       vision: 10.0,
       attack: default(),
       patrol: default(),
+      jump: default(),
     }
   }
 }
 
-// todo!> serializable parameter
-#[derive(Reflect)]
+/// Kills the enemy, the ones placed in the level stay dead in the save.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct Kill {
+  pub entity: Entity,
+}
+
+#[derive(Reflect, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct Attack {
   pub range: f32,
   pub damage: f32,
@@ -47,7 +90,8 @@ impl Default for Attack {
   }
 }
 
-#[derive(Reflect)]
+#[derive(Reflect, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct Patrol {
   pub rays: u8,
   pub range: f32,
@@ -60,24 +104,83 @@ impl Default for Patrol {
 }
 
 fn spawn(
-  query: Query<(Entity, &Enemy), Added<Enemy>>,
-  mut meshes: ResMut<Assets<Mesh>>,
-  mut materials: ResMut<Assets<ColorMaterial>>,
+  query: Query<
+    (Entity, &Kind, Option<&Loot>, Option<&EntityIid>),
+    Added<Enemy>,
+  >,
+  enemies: Res<EnemyAssets>,
+  archetypes: Res<Assets<Archetype>>,
+  progress: Option<Res<Progress>>,
   mut commands: Commands,
 ) {
-  let radius = 0.33;
+  for (entity, &kind, loot, iid) in query.iter() {
+    if let (Some(iid), Some(progress)) = (iid, &progress)
+      && progress.killed.contains(&iid.to_string())
+    {
+      commands.entity(entity).despawn();
+      continue;
+    }
 
-  for (entity, _enemy) in query.iter() {
-    let mesh = meshes.add(Circle::new(radius));
-    let material = materials.add(Color::srgb(0.3, 0.2, 0.1));
+    let Some(archetype) = archetypes.get(enemies.archetype(kind)) else {
+      warn!("archetype `{kind:?}` is not loaded");
+      continue;
+    };
 
-    commands.entity(entity).insert((
-      physics::enemy(),
+    let mut entity = commands.entity(entity);
+    entity.insert((
+      archetype.stats.clone(),
+      Profile::Enemy.layers(),
       Controller::default(),
-      Collider::circle(radius - physics::COLLIDER_OFFSET),
+      Collider::from(archetype.collider),
       YSort::default(),
-      Mesh2d(mesh),
-      MeshMaterial2d(material),
+      archetype.look.sprite(),
     ));
+
+    // loot placed in the level overrides the archetype one
+    if loot.is_none_or(|Loot(loot)| loot.is_empty()) {
+      entity.insert(Loot(archetype.loot.clone()));
+    }
+
+    match archetype.behaviour {
+      // walkers collide and slide on their own
+      Behaviour::Walk => {
+        entity.insert((Chase::default(), Kinematic::default()))
+      }
+      Behaviour::Fly(boid) => entity.insert((Flying, boid)),
+    };
+  }
+}
+
+fn kill(
+  kill: On<Kill>,
+  iids: Query<&EntityIid, With<Enemy>>,
+  progress: Option<ResMut<Progress>>,
+  mut commands: Commands,
+) {
+  // enemies created by spawners have no iid and come back with the level
+  if let Ok(iid) = iids.get(kill.entity)
+    && let Some(mut progress) = progress
+  {
+    progress.killed.insert(iid.to_string());
+  }
+  commands.entity(kill.entity).despawn();
+}
+
+/// Kill plane below all levels
+fn fall(
+  enemies: Query<(Entity, &Transform2D), With<Enemy>>,
+  levels: Levels,
+  mut commands: Commands,
+) {
+  let Some(bottom) =
+    levels.iter().map(|(.., bounds)| bounds.min.y).reduce(f32::min)
+  else {
+    return;
+  };
+
+  for (entity, transform) in enemies.iter() {
+    if transform.translation.y < bottom - KILL_DEPTH {
+      commands.trigger(Kill { entity });
+    }
   }
 }
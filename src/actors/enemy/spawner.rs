@@ -0,0 +1,158 @@
+use {
+  super::{Enemy, Kind},
+  crate::{
+    actors::{Player, env::Door},
+    level::ldtk::RegisterLdtkFields,
+    prelude::*,
+  },
+};
+
+#[derive(Reflect, Clone, LazyTimer)]
+#[reflect(Component)]
+pub struct SpawnTimer(#[timer] pub Timer);
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Spawner>()
+    .register_ldtk_entity::<SpawnerBundle>("Spawner")
+    .register_ldtk_fields::<Spawner>("Spawner")
+    .add_systems(
+      Update,
+      // `clear` goes before `spawn` to see the last spawned enemy
+      (activate, clear, spawn).chain().in_set(Systems::Update),
+    );
+}
+
+/// Spawns a wave of enemies once the player comes close.
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
+pub struct Spawner {
+  pub archetype: Kind,
+  pub count: u32,
+  /// seconds between spawns
+  pub interval: f32,
+  /// distance to the player that starts the wave
+  pub radius: f32,
+  /// iids of the doors locked until the wave is cleared
+  pub doors: Vec<String>,
+  pub spawned: u32,
+  pub wave: Wave,
+}
+
+impl Default for Spawner {
+  fn default() -> Self {
+    Self {
+      archetype: default(),
+      count: 1,
+      interval: 1.0,
+      radius: 96.0,
+      doors: vec![],
+      spawned: 0,
+      wave: default(),
+    }
+  }
+}
+
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wave {
+  #[default]
+  Idle,
+  Active,
+  Cleared,
+}
+
+/// Enemy spawned by the [`Spawner`].
+#[derive(Component)]
+#[relationship(relationship_target = Spawned)]
+pub struct SpawnedBy(pub Entity);
+
+/// Alive enemies of the [`Spawner`].
+#[derive(Component)]
+#[relationship_target(relationship = SpawnedBy)]
+pub struct Spawned(Vec<Entity>);
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct SpawnerBundle {
+  pub spawner: Spawner,
+  #[from_entity_instance]
+  entity_instance: EntityInstance,
+}
+
+fn lock(
+  iids: &[String],
+  locked: bool,
+  doors: &mut Query<(&EntityIid, &mut Door)>,
+) {
+  for (iid, mut door) in doors.iter_mut() {
+    if iids.iter().any(|other| other == iid.as_str()) {
+      door.locked = locked;
+    }
+  }
+}
+
+fn activate(
+  mut spawners: Query<(Entity, &mut Spawner, &GlobalTransform2D)>,
+  players: Query<&GlobalTransform2D, With<Player>>,
+  mut doors: Query<(&EntityIid, &mut Door)>,
+  mut commands: Commands,
+) {
+  for (entity, mut spawner, transform) in spawners.iter_mut() {
+    let position = transform.translation();
+    if spawner.wave == Wave::Idle
+      && players
+        .iter()
+        .any(|player| player.translation().distance(position) < spawner.radius)
+    {
+      spawner.wave = Wave::Active;
+      commands.entity(entity).insert(SpawnTimer::new(spawner.interval));
+      lock(&spawner.doors, true, &mut doors);
+    }
+  }
+}
+
+fn clear(
+  mut spawners: Query<(Entity, &mut Spawner, Option<&Spawned>)>,
+  mut doors: Query<(&EntityIid, &mut Door)>,
+  mut commands: Commands,
+) {
+  for (entity, mut spawner, alive) in spawners.iter_mut() {
+    if spawner.wave == Wave::Active
+      && spawner.spawned >= spawner.count
+      && alive.is_none_or(|alive| alive.is_empty())
+    {
+      spawner.wave = Wave::Cleared;
+      commands.entity(entity).remove::<SpawnTimer>();
+      lock(&spawner.doors, false, &mut doors);
+    }
+  }
+}
+
+fn spawn(
+  mut spawners: Query<(
+    Entity,
+    &mut Spawner,
+    &SpawnTimer,
+    &Transform2D,
+    &ChildOf,
+  )>,
+  mut commands: Commands,
+) {
+  for (entity, mut spawner, timer, &transform, child_of) in spawners.iter_mut()
+  {
+    if spawner.wave == Wave::Active
+      && spawner.spawned < spawner.count
+      && timer.just_finished()
+    {
+      spawner.spawned += 1;
+      // siblings of the spawner, so they unload with the level
+      commands.spawn((
+        Name::new("Enemy"),
+        Enemy,
+        spawner.archetype,
+        SpawnedBy(entity),
+        transform,
+        ChildOf(child_of.parent()),
+      ));
+    }
+  }
+}
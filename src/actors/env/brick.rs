@@ -1,31 +1,298 @@
-use crate::{level::Obstacle, prelude::*};
+use {
+  crate::{
+    actors::{Player, item::Item},
+    level::{
+      Obstacle, RegisterPersistent,
+      ldtk::{MapFields, RegisterLdtkFields},
+    },
+    prelude::*,
+    save::Progress,
+  },
+  bevy::ecs::entity::EntityHashSet,
+};
 
 pub fn plugin(app: &mut App) {
-  app.add_systems(Update, spawn.in_set(Systems::Spawn));
+  app
+    .register_type::<Brick>()
+    .register_type::<Pickup>()
+    .register_ldtk_entity::<BrickBundle>("Brick")
+    .register_ldtk_fields::<Brick>("Brick")
+    .register_persistent::<Brick>()
+    .add_systems(Update, spawn.after(MapFields).in_set(Systems::Spawn))
+    .add_systems(
+      Update,
+      (debris, pick.run_if(resource_exists::<Progress>))
+        .in_set(Systems::Update),
+    )
+    // reads the velocity once the player movement of the tick is in
+    .add_systems(
+      FixedUpdate,
+      bump.in_set(ControllerSystemSet).after(ControllerInputsSet),
+    )
+    .add_observer(hit);
 }
 
-#[derive(Component)]
+/// Damage of hitting the brick with the head
+const BUMP_DAMAGE: f32 = 1.0;
+const DEBRIS: usize = 6;
+const DEBRIS_LIFETIME: f32 = 0.8;
+const DEBRIS_GRAVITY: f32 = 400.0;
+
+/// Breaks after taking `health` damage, bumping it from below counts too.
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
 #[require(Obstacle)]
-pub struct Brick;
+pub struct Brick {
+  pub health: f32,
+  /// dropped once broken
+  pub drop: Vec<Item>,
+  pub size: Vec2,
+}
+
+impl Default for Brick {
+  fn default() -> Self {
+    Self { health: 3.0, drop: vec![], size: Vec2::splat(16.0) }
+  }
+}
+
+impl Brick {
+  pub fn is_broken(&self) -> bool {
+    self.health <= 0.0
+  }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct BrickBundle {
+  #[with(brick)]
+  pub brick: Brick,
+  #[from_entity_instance]
+  entity_instance: EntityInstance,
+}
+
+/// `health` and `drop` come from the LDtk fields
+fn brick(instance: &EntityInstance) -> Brick {
+  Brick {
+    size: Vec2::new(instance.width as f32, instance.height as f32),
+    ..default()
+  }
+}
+
+/// Damages the brick, does nothing to other entities.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct Hit {
+  pub entity: Entity,
+  pub damage: f32,
+}
+
+/// Item waiting on the ground to be picked up by the player.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct Pickup {
+  pub item: Item,
+  /// iid of the brick it dropped from
+  pub brick: String,
+}
+
+#[derive(Component)]
+struct Debris {
+  velocity: Vec2,
+  lifetime: Timer,
+}
 
 fn spawn(
-  query: Query<(Entity, &Brick), Added<Brick>>,
-  mut meshes: ResMut<Assets<Mesh>>,
-  mut materials: ResMut<Assets<ColorMaterial>>,
+  mut query: Query<(Entity, &EntityIid, &mut Brick), Added<Brick>>,
+  progress: Option<Res<Progress>>,
   mut commands: Commands,
 ) {
-  for (entity, _brick) in query.iter() {
-    let mesh = meshes.add(Rectangle::new(10.0, 10.0));
-    let material = materials.add(Color::srgb(0.0, 0.0, 0.0));
+  for (entity, iid, mut brick) in query.iter_mut() {
+    if let Some(progress) = &progress
+      && progress.broken.contains(&iid.to_string())
+    {
+      brick.health = 0.0;
+    }
+    if brick.is_broken() {
+      commands.entity(entity).remove::<Obstacle>();
+      continue;
+    }
 
+    let Vec2 { x, y } = brick.size;
     commands.entity(entity).insert((
-      physics::env(),
+      Sprite::from_color(Color::BLACK, brick.size),
       RigidBody::Static,
-      Collider::rectangle(10.0, 10.0),
-      // todo!> maybe create custom sprite component?
-      YSort::default(),
-      Mesh2d(mesh),
-      MeshMaterial2d(material),
+      Collider::rectangle(x, y),
+      Profile::Terrain.layers(),
     ));
   }
 }
+
+/// Hits the bricks the rising player is about to touch with the head.
+///
+/// The jump keeps pushing the player up for a few ticks after the contact,
+/// so only the first tick of touching a brick counts.
+fn bump(
+  player: Single<(&ColliderAabb, &LinearVelocity), With<Player>>,
+  bricks: Query<(Entity, &Brick, &Position)>,
+  mut touching: Local<EntityHashSet>,
+  time: Res<Time>,
+  mut commands: Commands,
+) {
+  let (aabb, velocity) = player.into_inner();
+  let head = aabb.max.y + velocity.y.max(0.0) * time.delta_secs();
+  let center = (aabb.min.x + aabb.max.x) / 2.0;
+
+  let mut touched = EntityHashSet::default();
+  for (entity, brick, position) in bricks.iter() {
+    let bottom = position.y - brick.size.y / 2.0;
+    let half = brick.size.x / 2.0;
+    if !brick.is_broken()
+      && (position.x - half..position.x + half).contains(&center)
+      && aabb.max.y <= bottom + 1.0
+      && head > bottom
+    {
+      touched.insert(entity);
+    }
+  }
+
+  if velocity.y > 0.0 {
+    for &entity in touched.difference(&touching) {
+      commands.trigger(Hit { entity, damage: BUMP_DAMAGE });
+    }
+  }
+  *touching = touched;
+}
+
+fn hit(
+  hit: On<Hit>,
+  mut bricks: Query<(&EntityIid, &mut Brick, &GlobalTransform2D)>,
+  progress: Option<ResMut<Progress>>,
+  mut commands: Commands,
+) {
+  let Ok((iid, mut brick, transform)) = bricks.get_mut(hit.entity) else {
+    return;
+  };
+  if brick.is_broken() {
+    return;
+  }
+
+  brick.health -= hit.damage;
+  if !brick.is_broken() {
+    return;
+  }
+
+  // with a drop the brick stays broken only once the drop is collected
+  if let Some(mut progress) = progress
+    && brick.drop.is_empty()
+  {
+    progress.broken.insert(iid.to_string());
+  }
+  commands.entity(hit.entity).remove::<(
+    Sprite,
+    RigidBody,
+    Collider,
+    CollisionLayers,
+    Obstacle,
+  )>();
+
+  let center = transform.translation();
+  let mut rng = rand::rng();
+  for _ in 0..DEBRIS {
+    let velocity =
+      Vec2::new(rng.random_range(-60.0..60.0), rng.random_range(60.0..160.0));
+    commands.spawn((
+      Debris {
+        velocity,
+        lifetime: Timer::from_seconds(DEBRIS_LIFETIME, TimerMode::Once),
+      },
+      Sprite::from_color(STONE_800, Vec2::splat(brick.size.x / 4.0)),
+      Transform2D::from_translation(center).with_layer(1.0),
+      DespawnOnExit(Game::Gameplay),
+    ));
+  }
+
+  for item in brick.drop.drain(..) {
+    commands.spawn((
+      Pickup { item, brick: iid.to_string() },
+      Sprite::from_color(AMBER_400, Vec2::splat(6.0)),
+      Transform2D::from_translation(center).with_layer(1.0),
+      // falls to the ground, the player walks through it
+      RigidBody::Dynamic,
+      LockedAxes::ROTATION_LOCKED,
+      Collider::rectangle(6.0, 6.0),
+      Profile::Pickup.layers(),
+      DespawnOnExit(Game::Gameplay),
+    ));
+  }
+}
+
+fn debris(
+  mut query: Query<(Entity, &mut Debris, &mut Transform2D)>,
+  time: Res<Time>,
+  mut commands: Commands,
+) {
+  let dt = time.delta_secs();
+  for (entity, mut debris, mut transform) in query.iter_mut() {
+    if debris.lifetime.tick(time.delta()).is_finished() {
+      commands.entity(entity).despawn();
+      continue;
+    }
+    debris.velocity.y -= DEBRIS_GRAVITY * dt;
+    transform.translation += debris.velocity * dt;
+    transform.rotate_z(debris.velocity.x.signum() * 8.0 * dt);
+  }
+}
+
+/// Radius of picking up the dropped items
+const PICK_RADIUS: f32 = 12.0;
+
+/// Picks up the dropped items near the player, the brick is recorded as
+/// broken with the last one of its drop.
+fn pick(
+  pickups: Query<(Entity, &Pickup, &GlobalTransform2D)>,
+  player: Single<&GlobalTransform2D, With<Player>>,
+  mut progress: ResMut<Progress>,
+  mut commands: Commands,
+) {
+  let player = player.translation();
+  let picked: EntityHashSet = pickups
+    .iter()
+    .filter(|(.., transform)| {
+      transform.translation().distance(player) < PICK_RADIUS
+    })
+    .map(|(entity, ..)| entity)
+    .collect();
+
+  for (entity, pickup, _) in pickups.iter_many(&picked) {
+    progress.inventory.push(pickup.item);
+    commands.entity(entity).despawn();
+
+    let left = pickups.iter().any(|(other, rest, _)| {
+      rest.brick == pickup.brick && !picked.contains(&other)
+    });
+    if !left {
+      progress.broken.insert(pickup.brick.clone());
+    }
+  }
+}
+
+#[test]
+fn broken_once_collected() {
+  let mut app = App::new();
+  app.init_resource::<Progress>().add_systems(Update, pick);
+  app.world_mut().spawn((Player, GlobalTransform2D::default()));
+  let at = |x| GlobalTransform2D::from(Transform2D::from_xy(x, 0.0));
+  let (near, far) = (at(4.0), at(64.0));
+  let pickup = |item| Pickup { item, brick: "brick".into() };
+  app.world_mut().spawn((pickup(Item::Gem), near));
+  let other = app.world_mut().spawn((pickup(Item::Meat), far)).id();
+
+  app.update();
+  let progress = app.world().resource::<Progress>();
+  assert_eq!(progress.inventory.len(), 1);
+  assert!(progress.broken.is_empty());
+
+  app.world_mut().entity_mut(other).insert(near);
+  app.update();
+  let progress = app.world().resource::<Progress>();
+  assert_eq!(progress.inventory.len(), 2);
+  assert!(progress.broken.contains("brick"));
+}
@@ -0,0 +1,82 @@
+use crate::{
+  actors::{Player, item::Item},
+  level::RegisterPersistent,
+  prelude::*,
+  save::Progress,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Chest>()
+    .register_ldtk_entity::<ChestBundle>("Chest")
+    .register_persistent::<Chest>()
+    .add_systems(
+      Update,
+      (spawn.in_set(Systems::Spawn), open.in_set(Systems::Update))
+        .run_if(resource_exists::<Progress>),
+    );
+}
+
+const CLOSED: Srgba = AMBER_700;
+const OPENED: Srgba = STONE_600;
+
+/// Gives its content to the player on touch, once per save.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct Chest {
+  pub content: Vec<Item>,
+  pub size: Vec2,
+  pub opened: bool,
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct ChestBundle {
+  #[with(chest)]
+  pub chest: Chest,
+}
+
+fn chest(instance: &EntityInstance) -> Chest {
+  Chest {
+    content: Item::from_field(instance, "content"),
+    size: Vec2::new(instance.width as f32, instance.height as f32),
+    opened: false,
+  }
+}
+
+fn color(opened: bool) -> Srgba {
+  if opened { OPENED } else { CLOSED }
+}
+
+fn spawn(
+  mut query: Query<(Entity, &EntityIid, &mut Chest), Added<Chest>>,
+  progress: Res<Progress>,
+  mut commands: Commands,
+) {
+  for (entity, iid, mut chest) in query.iter_mut() {
+    if progress.opened.contains(&iid.to_string()) {
+      chest.opened = true;
+      chest.content.clear();
+    }
+    commands
+      .entity(entity)
+      .insert(Sprite::from_color(color(chest.opened), chest.size));
+  }
+}
+
+fn open(
+  mut chests: Query<(&EntityIid, &mut Chest, &mut Sprite, &GlobalTransform2D)>,
+  player: Single<&GlobalTransform2D, With<Player>>,
+  mut progress: ResMut<Progress>,
+) {
+  for (iid, mut chest, mut sprite, transform) in chests.iter_mut() {
+    let bounds = Rect::from_center_size(transform.translation(), chest.size);
+    if chest.opened || !bounds.contains(player.translation()) {
+      continue;
+    }
+
+    chest.opened = true;
+    sprite.color = color(true).into();
+    progress.inventory.extend(chest.content.drain(..));
+    progress.opened.insert(iid.to_string());
+  }
+}
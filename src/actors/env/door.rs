@@ -0,0 +1,54 @@
+use crate::{level::ldtk::RegisterLdtkFields, prelude::*};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Door>()
+    .register_ldtk_entity::<DoorBundle>("Door")
+    .register_ldtk_fields::<Door>("Door")
+    .add_systems(Update, lock.in_set(Systems::Update));
+}
+
+/// Blocks the way while `locked`.
+///
+/// Not persistent, it is locked by the spawner waves, which start over when
+/// their level respawns.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component, Default)]
+pub struct Door {
+  pub locked: bool,
+  pub size: Vec2,
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct DoorBundle {
+  #[with(door)]
+  pub door: Door,
+  #[from_entity_instance]
+  entity_instance: EntityInstance,
+}
+
+/// `locked` comes from the LDtk fields
+fn door(instance: &EntityInstance) -> Door {
+  Door {
+    size: Vec2::new(instance.width as f32, instance.height as f32),
+    ..default()
+  }
+}
+
+fn lock(query: Query<(Entity, &Door), Changed<Door>>, mut commands: Commands) {
+  for (entity, &Door { locked, size }) in query.iter() {
+    let mut entity = commands.entity(entity);
+    if locked {
+      entity.insert((
+        Sprite::from_color(STONE_700, size),
+        RigidBody::Static,
+        Collider::rectangle(size.x, size.y),
+        Profile::Terrain.layers(),
+      ));
+    } else {
+      entity
+        .insert(Sprite::from_color(STONE_700.with_alpha(0.25), size))
+        .remove::<(RigidBody, Collider, CollisionLayers)>();
+    }
+  }
+}
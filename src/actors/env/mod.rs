@@ -1,9 +1,17 @@
 mod brick;
+mod chest;
+mod door;
+mod platform;
 
 use crate::prelude::*;
 
-pub use brick::Brick;
+pub use {brick::Brick, door::Door, platform::Platform};
 
 pub fn plugin(app: &mut App) {
-  app.add_plugins(brick::plugin);
+  app.add_plugins((
+    brick::plugin,
+    chest::plugin,
+    door::plugin,
+    platform::plugin,
+  ));
 }
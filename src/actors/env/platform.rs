@@ -0,0 +1,239 @@
+use crate::{
+  actors::{Player, player::Health},
+  level::ldtk::{MapFields, RegisterLdtkFields},
+  prelude::*,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Platform>()
+    .register_ldtk_entity::<PlatformBundle>("Platform")
+    .register_ldtk_fields::<Platform>("Platform")
+    .add_systems(Update, spawn.after(MapFields).in_set(Systems::Spawn))
+    // velocities are set before the player movement reads them to carry
+    .add_systems(
+      FixedUpdate,
+      travel.in_set(ControllerSystemSet).before(ControllerInputsSet),
+    );
+}
+
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathMode {
+  /// back and forth along the path
+  #[default]
+  PingPong,
+  /// back to the start from the last point
+  Loop,
+}
+
+/// Kinematic platform following the `path` from its LDtk position.
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
+pub struct Platform {
+  /// grid cells of the level
+  pub path: Vec<IVec2>,
+  /// pixels per second
+  pub speed: f32,
+  pub mode: PathMode,
+  pub size: Vec2,
+}
+
+impl Default for Platform {
+  fn default() -> Self {
+    Self { path: vec![], speed: 32.0, mode: default(), size: Vec2::splat(16.0) }
+  }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct PlatformBundle {
+  #[with(platform)]
+  pub platform: Platform,
+  #[from_entity_instance]
+  entity_instance: EntityInstance,
+}
+
+/// `path`, `speed` and `mode` come from the LDtk fields
+fn platform(instance: &EntityInstance) -> Platform {
+  Platform {
+    size: Vec2::new(instance.width as f32, instance.height as f32),
+    ..default()
+  }
+}
+
+/// Path offsets from the spawn point, the first one is zero.
+///
+/// Kinematic bodies move exactly by their velocity, so the platform keeps
+/// track of its own offset instead of waiting for the transforms.
+#[derive(Component)]
+struct Route {
+  points: Vec<Vec2>,
+  offset: Vec2,
+  next: usize,
+  backwards: bool,
+}
+
+impl Route {
+  fn advance(&mut self, mode: PathMode) {
+    let last = self.points.len() - 1;
+    match mode {
+      PathMode::Loop => self.next = (self.next + 1) % self.points.len(),
+      PathMode::PingPong => {
+        if self.next == last {
+          self.backwards = true;
+        } else if self.next == 0 {
+          self.backwards = false;
+        }
+        self.next = if self.backwards { self.next - 1 } else { self.next + 1 };
+      }
+    }
+  }
+}
+
+fn spawn(
+  query: Query<(Entity, &Platform, &EntityInstance, &ChildOf), Added<Platform>>,
+  layers: Query<&LayerMetadata>,
+  mut commands: Commands,
+) {
+  for (entity, platform, instance, child_of) in query.iter() {
+    let grid_size = layers
+      .get(child_of.parent())
+      .map_or(16.0, |layer| layer.grid_size as f32);
+
+    // LDtk grid goes down
+    let points = std::iter::once(Vec2::ZERO)
+      .chain(platform.path.iter().map(|&point| {
+        let offset = (point - instance.grid).as_vec2() * grid_size;
+        Vec2::new(offset.x, -offset.y)
+      }))
+      .collect();
+
+    let Vec2 { x, y } = platform.size;
+    commands.entity(entity).insert((
+      Route { points, offset: Vec2::ZERO, next: 0, backwards: false },
+      RigidBody::Kinematic,
+      Interpolation::default(),
+      Collider::rectangle(x, y),
+      Profile::Terrain.layers(),
+      Sprite::from_color(EMERALD_700, platform.size),
+    ));
+  }
+}
+
+/// Moves platforms toward the next point.
+///
+/// The player in the way is pushed by the physics, one squeezed against the
+/// walls is killed, so the platforms never get stuck.
+fn travel(
+  mut platforms: Query<(
+    Entity,
+    &Platform,
+    &mut Route,
+    &Position,
+    &mut LinearVelocity,
+  )>,
+  mut player: Option<
+    Single<
+      (Entity, &Position, &Collider, &ColliderAabb, &mut Health),
+      With<Player>,
+    >,
+  >,
+  spatial: SpatialQuery,
+  time: Res<Time>,
+) {
+  let dt = time.delta_secs();
+  if dt <= 0.0 {
+    return;
+  }
+
+  for (entity, platform, mut route, position, mut velocity) in
+    platforms.iter_mut()
+  {
+    if route.points.len() < 2 {
+      continue;
+    }
+
+    let step = platform.speed * dt;
+    if route.points[route.next].distance(route.offset) < 0.01 {
+      route.advance(platform.mode);
+    }
+    let delta = route.points[route.next] - route.offset;
+    velocity.0 = delta.clamp_length_max(step) / dt;
+
+    if let Some((player, player_pos, collider, aabb, health)) =
+      player.as_deref_mut()
+    {
+      let next =
+        Rect::from_center_size(position.0 + velocity.0 * dt, platform.size);
+      let body = Rect::from_corners(aabb.min, aabb.max);
+      // standing on top is carrying, not squeezing
+      let on_top = body.min.y >= next.max.y - 1.0;
+
+      if !on_top
+        && !next.intersect(body).is_empty()
+        && let Ok(direction) = Dir2::new(velocity.0)
+      {
+        let filter = SpatialQueryFilter::from_mask(Layers::Terrain)
+          .with_excluded_entities([entity, *player]);
+        let config = ShapeCastConfig::from_max_distance(step);
+        let origin = player_pos.0;
+        if spatial
+          .cast_shape(collider, origin, 0.0, direction, &config, &filter)
+          .is_some()
+        {
+          // nowhere to push the player to
+          health.0 = 0.0;
+        }
+      }
+    }
+
+    route.offset += velocity.0 * dt;
+  }
+}
+
+#[test]
+fn squeeze() {
+  let mut app = crate::core::test_app();
+  app.add_systems(FixedUpdate, travel);
+  let world = app.world_mut();
+
+  world.spawn((
+    RigidBody::Static,
+    Collider::rectangle(16.0, 64.0),
+    Profile::Terrain.layers(),
+    Transform2D::from_xy(40.0, 0.0),
+  ));
+  let player = world
+    .spawn((
+      Player,
+      RigidBody::Dynamic,
+      GravityScale(0.0),
+      Collider::rectangle(8.0, 8.0),
+      Profile::Player.layers(),
+      Transform2D::from_xy(20.0, 0.0),
+    ))
+    .id();
+  let platform = world
+    .spawn((
+      Platform { speed: 64.0, ..default() },
+      Route {
+        points: vec![Vec2::ZERO, Vec2::X * 64.0],
+        offset: Vec2::ZERO,
+        next: 0,
+        backwards: false,
+      },
+      RigidBody::Kinematic,
+      Collider::rectangle(16.0, 16.0),
+      Profile::Terrain.layers(),
+      Transform2D::default(),
+    ))
+    .id();
+
+  // a second of pushing the player into the wall
+  for _ in 0..64 {
+    app.update();
+  }
+  let world = app.world();
+  assert_eq!(world.get::<Health>(player).unwrap().0, 0.0);
+  // the platform goes on
+  assert!(world.get::<Route>(platform).unwrap().offset.x > 48.0);
+}
@@ -0,0 +1,51 @@
+use {
+  crate::prelude::*,
+  serde::{Deserialize, Serialize},
+};
+
+pub fn plugin(app: &mut App) {
+  app.register_type::<Item>().register_type::<Loot>();
+}
+
+/// Mirrors the `Item` enum of the LDtk project.
+#[derive(
+  Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+pub enum Item {
+  Knife,
+  HealingPlant,
+  Meat,
+  Boots,
+  Water,
+  Gem,
+}
+
+impl Item {
+  pub fn from_ldtk(id: &str) -> Option<Self> {
+    Some(match id {
+      "Knife" => Self::Knife,
+      "Healing_Plant" => Self::HealingPlant,
+      "Meat" => Self::Meat,
+      "Boots" => Self::Boots,
+      "Water" => Self::Water,
+      "Gem" => Self::Gem,
+      _ => return None,
+    })
+  }
+
+  /// Reads an `Array<LocalEnum.Item>` field, unknown values are skipped
+  pub fn from_field(instance: &EntityInstance, field: &str) -> Vec<Self> {
+    instance
+      .get_maybe_enums_field(field)
+      .into_iter()
+      .flatten()
+      .flatten()
+      .filter_map(|id| Self::from_ldtk(id))
+      .collect()
+  }
+}
+
+/// Items dropped on death.
+#[derive(Component, Reflect, Default, Clone, Debug)]
+#[reflect(Component)]
+pub struct Loot(pub Vec<Item>);
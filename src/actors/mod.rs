@@ -1,5 +1,6 @@
 pub mod enemy;
 pub mod env;
+pub mod item;
 pub mod player;
 
 use crate::prelude::*;
@@ -7,5 +8,5 @@ use crate::prelude::*;
 pub use {enemy::Enemy, env::Brick, player::Player};
 
 pub fn plugin(app: &mut App) {
-  app.add_plugins((player::plugin, enemy::plugin, env::plugin));
+  app.add_plugins((player::plugin, enemy::plugin, env::plugin, item::plugin));
 }
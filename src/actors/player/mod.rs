@@ -1,32 +1,39 @@
 mod assets;
 mod input;
 mod ldtk;
+mod respawn;
 mod state;
 
 use crate::prelude::*;
 
-pub use state::Grounded;
+pub use {respawn::Death, state::Grounded};
 
-background_timer!(StepsTimer);
+#[derive(Reflect, Clone, LazyTimer)]
+#[reflect(Component)]
+pub struct StepsTimer(#[timer] pub Timer);
 
 pub fn plugin(app: &mut App) {
   register(app)
-    .add_plugins((assets::plugin, state::plugin, input::plugin, ldtk::plugin))
-    .add_systems(
-      Update,
-      (spawn, steps.run_if(in_state(Game::Gameplay))).in_set(Systems::Spawn),
-    );
+    .add_plugins((
+      assets::plugin,
+      state::plugin,
+      input::plugin,
+      ldtk::plugin,
+      respawn::plugin,
+    ))
+    .add_systems(Update, spawn.in_set(Systems::Spawn))
+    .add_observer(steps);
 }
 
 fn register(app: &mut App) -> &mut App {
   app
     .register_type::<Stats>()
+    .register_type::<Health>()
     .register_type::<Player>()
-    .register_timer::<StepsTimer>()
 }
 
 #[derive(Component, Reflect, Default, Clone)]
-#[require(Stats)]
+#[require(Stats, Health, Snapshotted)]
 pub struct Player;
 
 #[derive(Component, Reflect)]
@@ -40,6 +47,19 @@ impl Default for Stats {
   }
 }
 
+pub const MAX_HEALTH: f32 = 3.0;
+
+/// The player dies at zero, restored on respawn.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Health(pub f32);
+
+impl Default for Health {
+  fn default() -> Self {
+    Self(MAX_HEALTH)
+  }
+}
+
 #[derive(Component)]
 pub enum WallCaster {
   Left,
@@ -63,6 +83,8 @@ fn spawn(
       .insert((input::map(), state::Controller))
       .insert((
         RigidBody::Dynamic,
+        Interpolation::default(),
+        GravityScale(1.0),
         LockedAxes::ROTATION_LOCKED,
         Mesh2d(mesh),
         MeshMaterial2d(material),
@@ -73,10 +95,7 @@ fn spawn(
         Rotation::default(),
         Collider::rectangle(12.0, 16.0), // 12 x 16
       )]))
-      .insert(CollisionLayers::new(
-        Layers::PlayerCollider,
-        [Layers::Terrain, Layers::Platform],
-      ))
+      .insert(Profile::Player.layers())
       .insert(
         ShapeCaster::new(
           Collider::rectangle(11.8, 0.5),
@@ -127,17 +146,19 @@ fn spawn(
 const TIME_BETWEEN_STEPS: f32 = 0.5;
 
 fn steps(
+  finished: On<TimerFinished<StepsTimer>>,
   mut commands: Commands,
   steps: Res<StepsAssets>,
-  query: Query<(Entity, &StepsTimer), (With<Player>, With<Grounded>)>,
+  grounded: Query<(), (With<Player>, With<Grounded>)>,
+  state: Res<State<Game>>,
 ) {
-  let mut rng = rand::rng();
-  for (entity, timer) in query.iter() {
-    if timer.just_finished()
-      && let Some(effect) = steps.tiles.choose(&mut rng).cloned()
-    {
-      let half = PlaybackSettings::ONCE.with_volume(Volume::Linear(0.05));
-      commands.entity(entity).with_child(sound_effect_with(effect, half));
-    }
+  if *state.get() == Game::Gameplay
+    && grounded.contains(finished.entity)
+    && let Some(effect) = steps.tiles.choose(&mut rand::rng()).cloned()
+  {
+    let half = PlaybackSettings::ONCE.with_volume(Volume::Linear(0.05));
+    commands
+      .entity(finished.entity)
+      .with_child(sound_effect_with(effect, half));
   }
 }
@@ -0,0 +1,122 @@
+use {
+  super::{Health, MAX_HEALTH, state::MoveInfo},
+  crate::{
+    actors::Player,
+    level::{checkpoint::SpawnPoint, ldtk::Levels},
+    prelude::*,
+  },
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .add_message::<Death>()
+    .add_systems(Update, (wounds, die, fade).chain().in_set(Systems::Update));
+}
+
+/// A [`Message`] written when the player dies.
+#[derive(Message, Debug, Copy, Clone)]
+pub struct Death;
+
+const FADE_SECS: f32 = 0.4;
+
+fn wounds(
+  player: Single<&Health, (With<Player>, Changed<Health>)>,
+  mut deaths: MessageWriter<Death>,
+) {
+  if player.0 <= 0.0 {
+    deaths.write(Death);
+  }
+}
+
+#[derive(Component)]
+struct Fade {
+  timer: Timer,
+  phase: Phase,
+}
+
+enum Phase {
+  /// darken the screen, respawn at the end
+  Out,
+  /// show the respawned player
+  In,
+}
+
+fn die(
+  mut deaths: MessageReader<Death>,
+  fades: Query<(), With<Fade>>,
+  mut commands: Commands,
+) {
+  if deaths.read().last().is_some() && fades.is_empty() {
+    commands.spawn((
+      Name::new("Death Fade"),
+      Node { width: Percent(100.0), height: Percent(100.0), ..default() },
+      GlobalZIndex(1),
+      BackgroundColor(Color::BLACK.with_alpha(0.0)),
+      DespawnOnExit(Game::Gameplay),
+      Fade {
+        timer: Timer::from_seconds(FADE_SECS, TimerMode::Once),
+        phase: Phase::Out,
+      },
+    ));
+  }
+}
+
+fn fade(
+  time: Res<Time>,
+  mut fades: Query<(Entity, &mut Fade, &mut BackgroundColor)>,
+  mut commands: Commands,
+  spawn_point: Option<Res<SpawnPoint>>,
+  player: Single<
+    (
+      &mut Transform2D,
+      &mut LinearVelocity,
+      &mut MoveInfo,
+      &mut Health,
+      Option<&ChildOf>,
+    ),
+    With<Player>,
+  >,
+  parents: Query<&GlobalTransform2D>,
+  mut selection: ResMut<LevelSelection>,
+  levels: Levels,
+) {
+  let Ok((entity, mut fade, mut background)) = fades.single_mut() else {
+    return;
+  };
+
+  let fraction = fade.timer.tick(time.delta()).fraction();
+  let alpha = match fade.phase {
+    Phase::Out => fraction,
+    Phase::In => 1.0 - fraction,
+  };
+  background.0.set_alpha(alpha);
+
+  if !fade.timer.just_finished() {
+    return;
+  }
+
+  match fade.phase {
+    Phase::Out => {
+      fade.phase = Phase::In;
+      fade.timer.reset();
+
+      let Some(spawn_point) = spawn_point else { return };
+      let (mut transform, mut velocity, mut info, mut health, child_of) =
+        player.into_inner();
+      transform.translation =
+        world_to_local(spawn_point.position, child_of, &parents);
+      velocity.0 = Vec2::ZERO;
+      *info = MoveInfo::default();
+      health.0 = MAX_HEALTH;
+
+      // bring the level back to its initial state
+      *selection = LevelSelection::iid(spawn_point.level.clone());
+      if let Some((level, ..)) =
+        levels.iter().find(|(_, level, _)| level.iid == spawn_point.level)
+      {
+        commands.entity(level).insert(Respawn);
+      }
+    }
+    Phase::In => commands.entity(entity).despawn(),
+  }
+}
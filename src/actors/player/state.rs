@@ -1,5 +1,8 @@
 use {
-  crate::{actors::Player, prelude::*},
+  crate::{
+    actors::{Player, env::Platform},
+    prelude::*,
+  },
   avian2d::math::*,
 };
 
@@ -22,18 +25,27 @@ const PLAYER_JUMP_VEL: f32 = 2.2;
 const PLAYER_MOVE_VEL: f32 = 0.4;
 /// The y velocity subtracted from the player due to gravity.
 const PLAYER_GRAVITY: f32 = 0.15;
+/// Ground normals with a lower y are walls, not slopes.
+const SLOPE_MIN_NORMAL_Y: f32 = 0.5;
+/// Ground normals with a higher y are flat ground.
+const SLOPE_MAX_NORMAL_Y: f32 = 0.999;
+/// The velocity pressing the player into the slope while walking downhill.
+const SLOPE_STICK_VEL: f32 = 0.5;
 
 pub fn plugin(app: &mut App) {
   register(app)
     .add_systems(
       FixedUpdate,
-      (update_grounded, movement).chain().in_set(Systems::Update),
+      // the controller inputs go between moving the platforms and reading
+      // the velocity back
+      (update_grounded, movement).chain().in_set(ControllerInputsSet),
     )
     .add_systems(Update, keyboard_input.in_set(Systems::Input));
-  // restore velocity after pause
+  // restore velocity after pause, the gameplay entities outlive it
   app
-    .add_systems(OnExit(Game::Gameplay), cache::store::<LinearVelocity, ()>)
-    .add_systems(OnEnter(Game::Gameplay), cache::restore::<LinearVelocity, ()>);
+    .register_snapshot::<LinearVelocity>()
+    .add_systems(OnEnter(Pause(true)), snapshot::store)
+    .add_systems(OnEnter(Pause(false)), snapshot::restore);
 }
 
 fn register(app: &mut App) -> &mut App {
@@ -65,6 +77,8 @@ pub struct MoveInfo {
   pub coyote_time_ticks: isize,
   pub jump_boost_ticks: isize,
   pub walk: bool, // just relax
+  /// velocity of the kinematic body the player stands on
+  pub carry: Vector,
 }
 
 pub fn keyboard_input(
@@ -112,12 +126,23 @@ pub fn update_grounded(
 pub fn movement(
   mut events: MessageReader<Action>,
   player: Single<
-    (&mut MoveInfo, &mut LinearVelocity, &ShapeHits, Has<Grounded>),
+    (
+      &mut MoveInfo,
+      &mut LinearVelocity,
+      &mut GravityScale,
+      &ShapeHits,
+      Has<Grounded>,
+    ),
     With<Player>,
   >,
   wall_casters: Query<(&ShapeHits, &super::WallCaster), Without<Player>>,
+  platforms: Query<&LinearVelocity, (With<Platform>, Without<Player>)>,
 ) {
-  let (mut info, mut velocity, shape_hits, is_grounded) = player.into_inner();
+  let (mut info, mut velocity, mut gravity, shape_hits, is_grounded) =
+    player.into_inner();
+
+  // controls work relative to the moving ground
+  velocity.0 -= info.carry;
 
   if is_grounded {
     info.coyote_time_ticks = COYOTE_TIME_TICKS;
@@ -198,6 +223,35 @@ pub fn movement(
     PLAYER_MAX_H_VEL * 64. * walk_modifier,
   );
 
+  // follow the slope instead of flying off it downhill or sliding down
+  let slope = shape_hits
+    .iter()
+    .map(|hit| hit.normal1)
+    .filter(|normal| {
+      (SLOPE_MIN_NORMAL_Y..SLOPE_MAX_NORMAL_Y).contains(&normal.y)
+    })
+    .max_by(|a, b| a.y.total_cmp(&b.y));
+
+  gravity.0 = 1.0;
+  if is_grounded
+    && info.jump_boost_ticks <= 0
+    && let Some(normal) = slope
+  {
+    velocity.y = -velocity.x * normal.x / normal.y;
+    if moved {
+      // along the normal, so it never turns into sliding
+      velocity.0 -= normal * SLOPE_STICK_VEL * 64.;
+    }
+    gravity.0 = 0.0;
+  }
+
+  // merged walls are kinematic too, only the platforms move
+  info.carry = shape_hits
+    .iter()
+    .find_map(|hit| platforms.get(hit.entity).ok())
+    .map_or(Vector::ZERO, |platform| platform.0);
+  velocity.0 += info.carry;
+
   // todo! maybe use `MoveInfo`
   info.should_jump_ticks -= 1;
   info.jump_boost_ticks -= 1;
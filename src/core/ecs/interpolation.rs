@@ -0,0 +1,168 @@
+//! Smooth rendering of `Transform2D` moved in fixed steps.
+//!
+//! The fixed pose is put back before the fixed steps and the blended one is
+//! shown after them, so gameplay and physics never see the blended pose.
+
+use {
+  super::transform::{Synced, Transform2DSystems},
+  crate::prelude::*,
+  bevy::app::RunFixedMainLoopSystems,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .add_systems(
+      RunFixedMainLoop,
+      restore.in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop),
+    )
+    .add_systems(FixedLast, snapshot.after(Transform2DSystems::Propagate))
+    .add_systems(PostUpdate, blend.before(Transform2DSystems::Propagate));
+}
+
+/// Shows the `Transform2D` position and rotation between fixed steps.
+///
+/// Writes to the `Transform2D` outside of fixed steps are not smoothed.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[require(Transform2D, Poses)]
+pub enum Interpolation {
+  /// between the last two steps, a step behind
+  #[default]
+  Interpolate,
+  /// ahead of the last step, may overshoot
+  Extrapolate,
+}
+
+/// Skips the smoothing from the previous step, removed after the next one.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[component(storage = "SparseSet")]
+pub struct Teleport;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Pose {
+  translation: Vec2,
+  rotation: Rot2,
+}
+
+impl Pose {
+  fn of(transform: &Transform2D) -> Self {
+    Self { translation: transform.translation, rotation: transform.rotation }
+  }
+
+  fn apply(self, transform: &mut Transform2D) {
+    transform.translation = self.translation;
+    transform.rotation = self.rotation;
+  }
+
+  fn blend(self, to: Self, s: f32) -> Self {
+    Self {
+      translation: self.translation.lerp(to.translation, s),
+      rotation: self.rotation.slerp(to.rotation, s),
+    }
+  }
+}
+
+#[derive(Component, Default)]
+struct Poses {
+  previous: Option<Pose>,
+  current: Option<Pose>,
+  /// the last blended pose, anything else was written outside of fixed steps
+  shown: Option<Pose>,
+}
+
+impl Poses {
+  fn snap(&mut self, pose: Pose) {
+    *self = Self { previous: Some(pose), current: Some(pose), shown: None };
+  }
+}
+
+fn restore(
+  mut query: Query<(&mut Poses, &mut Transform2D, &mut Transform, &mut Synced)>,
+) {
+  for (mut poses, mut transform, mut transform_3d, mut synced) in
+    query.iter_mut()
+  {
+    let (Some(current), Some(shown)) = (poses.current, poses.shown.take())
+    else {
+      continue;
+    };
+    if Pose::of(&transform) != shown {
+      poses.snap(Pose::of(&transform));
+      continue;
+    }
+
+    // physics reads `Transform`, so it has to be put back as well
+    current.apply(&mut transform);
+    *transform_3d = Transform::from(*transform);
+    synced.0 = *transform;
+  }
+}
+
+fn snapshot(
+  mut query: Query<(Entity, &mut Poses, &Transform2D, Has<Teleport>)>,
+  mut commands: Commands,
+) {
+  for (entity, mut poses, transform, teleport) in query.iter_mut() {
+    let pose = Pose::of(transform);
+    if teleport {
+      commands.entity(entity).remove::<Teleport>();
+    }
+    if teleport || poses.current.is_none() {
+      poses.snap(pose);
+    } else {
+      poses.previous = poses.current;
+      poses.current = Some(pose);
+    }
+  }
+}
+
+fn blend(
+  mut query: Query<(&Interpolation, &mut Poses, &mut Transform2D)>,
+  time: Res<Time<Fixed>>,
+) {
+  let overstep = time.overstep_fraction();
+  for (interpolation, mut poses, mut transform) in query.iter_mut() {
+    let (Some(previous), Some(current)) = (poses.previous, poses.current)
+    else {
+      continue;
+    };
+    // moved outside of fixed steps, like a respawn
+    if Pose::of(&transform) != current {
+      poses.snap(Pose::of(&transform));
+      continue;
+    }
+
+    let pose = match interpolation {
+      Interpolation::Interpolate => previous.blend(current, overstep),
+      Interpolation::Extrapolate => previous.blend(current, 1.0 + overstep),
+    };
+    pose.apply(&mut transform);
+    poses.shown = Some(pose);
+  }
+}
+
+#[test]
+fn teleport_skips_blend() {
+  use bevy::ecs::system::RunSystemOnce;
+
+  let mut world = World::new();
+  world.init_resource::<Time<Fixed>>();
+  let entity = world.spawn(Interpolation::Interpolate).id();
+  let step = |world: &mut World, x: f32, teleport: bool| {
+    world.run_system_once(restore).unwrap();
+    world.get_mut::<Transform2D>(entity).unwrap().translation.x = x;
+    if teleport {
+      world.entity_mut(entity).insert(Teleport);
+    }
+    world.run_system_once(snapshot).unwrap();
+    world.run_system_once(blend).unwrap();
+    world.get::<Transform2D>(entity).unwrap().translation.x
+  };
+
+  step(&mut world, 0.0, false);
+  // without overstep the previous step is shown
+  assert_eq!(step(&mut world, 10.0, false), 0.0);
+  assert_eq!(step(&mut world, 20.0, true), 20.0);
+  assert!(!world.entity(entity).contains::<Teleport>());
+  // and blended again from the teleported pose
+  assert_eq!(step(&mut world, 30.0, false), 20.0);
+}
@@ -1,13 +1,24 @@
+mod interpolation;
 mod pause;
+mod time_scale;
 mod transform;
 
 use crate::prelude::*;
 
 pub use {
+  interpolation::{Interpolation, Teleport},
   pause::{PausableSystems, Pause},
-  transform::{Transform2D, Transform2DSystems},
+  time_scale::TimeScale,
+  transform::{
+    GlobalTransform2D, Transform2D, Transform2DSystems, world_to_local,
+  },
 };
 
 pub fn plugin(app: &mut App) {
-  app.add_plugins((pause::plugin, transform::plugin));
+  app.add_plugins((
+    interpolation::plugin,
+    pause::plugin,
+    time_scale::plugin,
+    transform::plugin,
+  ));
 }
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<TimeScale>()
+    .init_resource::<TimeScale>()
+    .add_systems(PreUpdate, apply);
+}
+
+/// Speed of the game time, `1.0` is the normal one.
+///
+/// The `base` speed is the player's setting, the slow-motion effects ramp on
+/// top of it in real time, so they take as long at any speed.
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct TimeScale {
+  pub base: f32,
+  effect: f32,
+  target: f32,
+  /// change of the `effect` per real second
+  rate: f32,
+}
+
+impl Default for TimeScale {
+  fn default() -> Self {
+    Self { base: 1.0, effect: 1.0, target: 1.0, rate: 0.0 }
+  }
+}
+
+impl TimeScale {
+  pub fn scale(&self) -> f32 {
+    self.base * self.effect
+  }
+
+  /// Slows the time down to the `scale` within `ramp` seconds.
+  pub fn slow_motion(&mut self, scale: f32, ramp: f32) {
+    self.target = scale.max(0.0);
+    if ramp > 0.0 {
+      self.rate = (self.target - self.effect).abs() / ramp;
+    } else {
+      self.effect = self.target;
+    }
+  }
+
+  /// Back to the `base` speed within `ramp` seconds.
+  pub fn release(&mut self, ramp: f32) {
+    self.slow_motion(1.0, ramp);
+  }
+
+  fn step(&mut self, real: f32) {
+    let delta = self.target - self.effect;
+    let step = self.rate * real;
+    self.effect = if delta.abs() <= step {
+      self.target
+    } else {
+      self.effect + delta.signum() * step
+    };
+  }
+}
+
+/// Scales the virtual time.
+///
+/// Fixed steps keep their length and run less often when slowed down,
+/// so per-step logic like the player movement behaves the same at any speed.
+/// Physics is stepped in the fixed schedule too, so it is slowed by the
+/// scale without touching its own clock.
+fn apply(
+  mut scale: ResMut<TimeScale>,
+  mut time: ResMut<Time<Virtual>>,
+  real: Res<Time<Real>>,
+) {
+  scale.step(real.delta_secs());
+  let speed = scale.scale();
+  if time.relative_speed() != speed {
+    time.set_relative_speed(speed);
+  }
+}
+
+#[test]
+fn ramps() {
+  let mut scale = TimeScale { base: 0.5, ..default() };
+  scale.slow_motion(0.2, 0.4);
+  scale.step(0.2);
+  assert!((scale.effect - 0.6).abs() < 1e-5);
+  assert!((scale.scale() - 0.3).abs() < 1e-5);
+
+  scale.step(1.0);
+  assert_eq!(scale.effect, 0.2);
+
+  // without a ramp the speed changes at once
+  scale.release(0.0);
+  assert_eq!(scale.scale(), 0.5);
+}
+
+#[test]
+fn physics_scaled_once() {
+  let mut app = crate::core::test_app();
+  app.world_mut().resource_mut::<TimeScale>().slow_motion(0.5, 0.0);
+  let body = app
+    .world_mut()
+    .spawn((
+      RigidBody::Dynamic,
+      GravityScale(0.0),
+      LinearVelocity(Vec2::X * 128.0),
+      Collider::circle(4.0),
+      Transform2D::default(),
+    ))
+    .id();
+
+  // two real seconds are one at half the speed
+  for _ in 0..128 {
+    app.update();
+  }
+  let travelled = app.world().get::<Position>(body).unwrap().x;
+  assert!((travelled - 128.0).abs() < 6.0, "{travelled}");
+}
@@ -1,33 +1,33 @@
-use crate::prelude::*;
+use {
+  crate::prelude::*,
+  bevy::{ecs::entity::EntityHashSet, math::Affine2},
+};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum Transform2DSystems {
-  /// Propagates changes in `Transform2D` to [`Transform`]
+  /// Syncs `Transform2D` with [`Transform`] before its propagation and
+  /// computes [`GlobalTransform2D`]
   Propagate,
 }
 
 pub fn plugin(app: &mut App) {
   app.configure_sets(
     PostUpdate,
-    Transform2DSystems::Propagate
-      .after(TransformSystems::Propagate),
+    Transform2DSystems::Propagate.before(TransformSystems::Propagate),
   );
-  app.register_type::<Transform2D>();
+  app.register_type::<Transform2D>().register_type::<GlobalTransform2D>();
   app.add_systems(First, spawn);
   app.add_systems(
     PostUpdate,
-    (
-      sync_3d.before(TransformSystems::Propagate),
-      sync_2d.in_set(Transform2DSystems::Propagate),
-    ),
+    (sync, propagate).chain().in_set(Transform2DSystems::Propagate),
   );
+  // physics writes `Transform` every step, keep 2D code of the next step
+  // and the interpolation up to date
+  app.add_systems(FixedLast, sync.in_set(Transform2DSystems::Propagate));
 }
 
-#[derive(Component)]
-struct Reprojection(Transform);
-
 fn spawn(
-  query: Query<(Entity, &Transform), Added<Transform>>,
+  query: Query<(Entity, &Transform), (Added<Transform>, Without<Transform2D>)>,
   mut commands: Commands,
 ) {
   for (entity, &transform) in query.iter() {
@@ -35,27 +35,110 @@ fn spawn(
   }
 }
 
-fn sync_2d(query: Query<(Entity, &Transform2D)>, mut commands: Commands) {
-  for (entity, &transform) in query.iter() {
-    let transform = Transform::from(transform);
-    commands.entity(entity).insert(transform).insert(Reprojection(transform));
+/// The value both transforms had after the last sync
+#[derive(Component, Default, Clone, Copy)]
+pub(super) struct Synced(pub(super) Transform2D);
+
+/// Keeps the fields changed in 2D, the rest is taken from [`Transform`]
+/// if something else (like physics) wrote it.
+///
+/// Only entities with a transform changed since the last run are visited,
+/// and unchanged values are not written, so change detection keeps working.
+fn sync(
+  mut query: Query<
+    (&mut Transform2D, &mut Transform, &mut Synced),
+    Or<(Changed<Transform2D>, Changed<Transform>)>,
+  >,
+) {
+  for (mut master, mut slave, mut synced) in query.iter_mut() {
+    let outer = Transform2D::from(*slave);
+    let value = if slave.is_changed() && outer != synced.0 {
+      merge(synced.0, *master, outer)
+    } else {
+      *master
+    };
+
+    master.set_if_neq(value);
+    slave.set_if_neq(Transform::from(value));
+    synced.0 = value;
   }
 }
 
-fn sync_3d(
-  mut query: Query<(&mut Transform2D, Option<&Transform>, &Reprojection)>,
+/// Computes the global transforms of the entities with a changed
+/// `Transform2D` or parent and of their descendants, like `sync` the rest
+/// of the hierarchy is not visited.
+fn propagate(
+  changed: Query<
+    Entity,
+    (With<Transform2D>, Or<(Changed<Transform2D>, Changed<ChildOf>)>),
+  >,
+  mut orphans: RemovedComponents<ChildOf>,
+  nodes: Query<(&Transform2D, Option<&ChildOf>)>,
+  parents: Query<&ChildOf>,
+  children: Query<&Children>,
+  mut globals: Query<&mut GlobalTransform2D>,
 ) {
-  for (mut master, slave, &Reprojection(proj)) in query.iter_mut() {
-    if let Some(&slave) = slave
-      && slave != proj
-    {
-      *master = Transform2D::from(slave);
+  fn visit(
+    entity: Entity,
+    global: GlobalTransform2D,
+    nodes: &Query<(&Transform2D, Option<&ChildOf>)>,
+    children: &Query<&Children>,
+    globals: &mut Query<&mut GlobalTransform2D>,
+  ) {
+    let Ok(mut current) = globals.get_mut(entity) else { return };
+    current.set_if_neq(global);
+
+    for &child in children.get(entity).into_iter().flatten() {
+      let Ok((transform, _)) = nodes.get(child) else { continue };
+      let global = global.mul_transform(transform);
+      visit(child, global, nodes, children, globals);
     }
   }
+
+  // roots of the changed subtrees, detached entities are roots now
+  let dirty: EntityHashSet = changed
+    .iter()
+    .chain(orphans.read().filter(|&entity| nodes.contains(entity)))
+    .collect();
+
+  for &entity in &dirty {
+    // visited from the changed ancestor instead
+    if parents.iter_ancestors(entity).any(|parent| dirty.contains(&parent)) {
+      continue;
+    }
+    let Ok((transform, child_of)) = nodes.get(entity) else { continue };
+    let global = match child_of {
+      Some(child_of) => match globals.get(child_of.parent()) {
+        Ok(parent) => parent.mul_transform(transform),
+        Err(_) => continue,
+      },
+      None => GlobalTransform2D::from(*transform),
+    };
+    visit(entity, global, &nodes, &children, &mut globals);
+  }
+}
+
+/// Takes the fields `ours` changed since `base`, the rest from `theirs`
+fn merge(
+  base: Transform2D,
+  ours: Transform2D,
+  theirs: Transform2D,
+) -> Transform2D {
+  fn pick<T: PartialEq>(base: T, ours: T, theirs: T) -> T {
+    if ours != base { ours } else { theirs }
+  }
+
+  Transform2D {
+    translation: pick(base.translation, ours.translation, theirs.translation),
+    rotation: pick(base.rotation, ours.rotation, theirs.rotation),
+    scale: pick(base.scale, ours.scale, theirs.scale),
+    layer: pick(base.layer, ours.layer, theirs.layer),
+  }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy, Component, Reflect)]
 #[reflect(Component, Default, PartialEq, Debug)]
+#[require(Transform, Synced, GlobalTransform2D)]
 pub struct Transform2D {
   pub translation: Vec2,
   pub rotation: Rot2,
@@ -116,6 +199,87 @@ impl Transform2D {
   pub fn translation(&self) -> Vec2 {
     self.translation
   }
+
+  pub fn to_affine(&self) -> Affine2 {
+    Affine2::from_scale_angle_translation(
+      self.scale,
+      self.rotation.as_radians(),
+      self.translation,
+    )
+  }
+}
+
+/// World space [`Transform2D`], computed through the [`ChildOf`] hierarchy
+/// in [`Transform2DSystems::Propagate`].
+///
+/// Entities under a parent without `Transform2D` are not updated.
+#[derive(Debug, PartialEq, Clone, Copy, Component, Reflect)]
+#[reflect(Component, Default, PartialEq, Debug)]
+pub struct GlobalTransform2D {
+  affine: Affine2,
+  layer: f32,
+}
+
+impl GlobalTransform2D {
+  pub const IDENTITY: Self = Self { affine: Affine2::IDENTITY, layer: 0.0 };
+
+  pub fn translation(&self) -> Vec2 {
+    self.affine.translation
+  }
+
+  pub fn rotation(&self) -> Rot2 {
+    Rot2::radians(self.affine.to_scale_angle_translation().1)
+  }
+
+  pub fn layer(&self) -> f32 {
+    self.layer
+  }
+
+  pub fn affine(&self) -> Affine2 {
+    self.affine
+  }
+
+  /// Local point of this entity to the world space
+  pub fn transform_point(&self, point: Vec2) -> Vec2 {
+    self.affine.transform_point2(point)
+  }
+
+  /// World point to the local space of this entity
+  pub fn inverse_transform_point(&self, point: Vec2) -> Vec2 {
+    self.affine.inverse().transform_point2(point)
+  }
+
+  /// Global transform of a child with the `transform`
+  pub fn mul_transform(&self, transform: &Transform2D) -> Self {
+    Self {
+      affine: self.affine * transform.to_affine(),
+      layer: self.layer + transform.layer,
+    }
+  }
+}
+
+/// World point to the `Transform2D` translation of an entity with the
+/// `child_of` parent, roots are in world space already.
+pub fn world_to_local(
+  point: Vec2,
+  child_of: Option<&ChildOf>,
+  parents: &Query<&GlobalTransform2D>,
+) -> Vec2 {
+  child_of
+    .and_then(|child_of| parents.get(child_of.parent()).ok())
+    .map_or(point, |parent| parent.inverse_transform_point(point))
+}
+
+impl Default for GlobalTransform2D {
+  fn default() -> Self {
+    Self::IDENTITY
+  }
+}
+
+impl From<Transform2D> for GlobalTransform2D {
+  fn from(transform: Transform2D) -> Self {
+    Self { affine: transform.to_affine(), layer: transform.layer }
+  }
 }
 
 impl Default for Transform2D {
@@ -153,3 +317,54 @@ fn up() {
 
   assert!(Vec2::new(1.0, 0.0).angle_to(transform.up()) < f32::EPSILON);
 }
+
+#[test]
+fn merge_fields() {
+  let base = Transform2D::from_xy(1.0, 1.0);
+  // physics moved it, y-sorting changed the layer
+  let theirs = Transform2D::from_xy(2.0, 3.0);
+  let ours = base.with_layer(0.5);
+
+  assert_eq!(merge(base, ours, theirs), theirs.with_layer(0.5));
+}
+
+#[test]
+fn world_points() {
+  let parent = GlobalTransform2D::from(
+    Transform2D::from_xy(10.0, 0.0).with_scale(Vec2::splat(2.0)),
+  );
+  let child = parent.mul_transform(&Transform2D {
+    rotation: Rot2::degrees(90.0),
+    ..Transform2D::from_xy(1.0, 0.0)
+  });
+
+  assert!(child.translation().distance(vec2(12.0, 0.0)) < 1e-5);
+  let world = child.transform_point(Vec2::X);
+  assert!(world.distance(vec2(12.0, 2.0)) < 1e-5);
+  assert!(child.inverse_transform_point(world).distance(Vec2::X) < 1e-5);
+}
+
+#[test]
+fn propagate_changed() {
+  let mut app = App::new();
+  app.add_plugins((TransformPlugin, plugin));
+  let world = app.world_mut();
+  let parent = world.spawn(Transform2D::from_xy(1.0, 0.0)).id();
+  let child =
+    world.spawn((Transform2D::from_xy(0.0, 2.0), ChildOf(parent))).id();
+  let global = |app: &App| {
+    app.world().get::<GlobalTransform2D>(child).unwrap().translation()
+  };
+
+  app.update();
+  assert!(global(&app).distance(vec2(1.0, 2.0)) < 1e-5);
+
+  app.world_mut().get_mut::<Transform2D>(parent).unwrap().translation.x = 3.0;
+  app.update();
+  assert!(global(&app).distance(vec2(3.0, 2.0)) < 1e-5);
+
+  // a detached child is a root
+  app.world_mut().entity_mut(child).remove::<ChildOf>();
+  app.update();
+  assert!(global(&app).distance(vec2(0.0, 2.0)) < 1e-5);
+}
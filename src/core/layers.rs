@@ -0,0 +1,114 @@
+//! The only collision layers of the game, bodies take them from a profile.
+
+use crate::prelude::*;
+
+#[derive(PhysicsLayer, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Layers {
+  #[default]
+  Default,
+  //
+  PlayerHurtbox,
+  PlayerCollider,
+  // all terrain types, gotta be separate for casters though
+  Terrain,
+  Platform,
+  /// sensors hurting the player
+  Hazard,
+  Enemy,
+  Projectile,
+  /// items lying on the ground
+  Pickup,
+}
+
+/// What an actor is made of and what it collides with.
+///
+/// Two profiles interact only if both of them list each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Profile {
+  Player,
+  /// walls, doors, bricks and moving platforms
+  Terrain,
+  /// one-way platforms
+  Platform,
+  Hazard,
+  Enemy,
+  Projectile,
+  Pickup,
+}
+
+impl Profile {
+  pub const ALL: [Self; 7] = [
+    Self::Player,
+    Self::Terrain,
+    Self::Platform,
+    Self::Hazard,
+    Self::Enemy,
+    Self::Projectile,
+    Self::Pickup,
+  ];
+
+  pub fn membership(self) -> Layers {
+    match self {
+      Self::Player => Layers::PlayerCollider,
+      Self::Terrain => Layers::Terrain,
+      Self::Platform => Layers::Platform,
+      Self::Hazard => Layers::Hazard,
+      Self::Enemy => Layers::Enemy,
+      Self::Projectile => Layers::Projectile,
+      Self::Pickup => Layers::Pickup,
+    }
+  }
+
+  pub fn filters(self) -> &'static [Layers] {
+    match self {
+      Self::Player => &[Layers::Terrain, Layers::Platform, Layers::Hazard],
+      Self::Terrain | Self::Platform => {
+        &[Layers::PlayerCollider, Layers::Enemy, Layers::Pickup]
+      }
+      Self::Hazard => &[Layers::PlayerCollider],
+      Self::Enemy => &[Layers::Terrain, Layers::Platform, Layers::Projectile],
+      Self::Projectile => &[Layers::Enemy],
+      Self::Pickup => &[Layers::Terrain, Layers::Platform],
+    }
+  }
+
+  pub fn layers(self) -> CollisionLayers {
+    let filters = self
+      .filters()
+      .iter()
+      .fold(LayerMask::NONE, |mask, &layer| mask | LayerMask::from(layer));
+    CollisionLayers::new(self.membership(), filters)
+  }
+
+  pub fn interacts(self, other: Self) -> bool {
+    self.layers().interacts_with(other.layers())
+  }
+}
+
+impl From<Profile> for CollisionLayers {
+  fn from(profile: Profile) -> Self {
+    profile.layers()
+  }
+}
+
+#[test]
+fn profile_matrix() {
+  use Profile::*;
+
+  let collides = [
+    (Player, Terrain),
+    (Player, Platform),
+    (Player, Hazard),
+    (Enemy, Terrain),
+    (Enemy, Platform),
+    (Enemy, Projectile),
+    (Pickup, Terrain),
+    (Pickup, Platform),
+  ];
+  for a in Profile::ALL {
+    for b in Profile::ALL {
+      let expected = collides.contains(&(a, b)) || collides.contains(&(b, a));
+      assert_eq!(a.interacts(b), expected, "{a:?} with {b:?}");
+    }
+  }
+}
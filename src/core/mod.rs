@@ -1,11 +1,12 @@
 pub mod audio;
-pub mod cache;
 mod debug;
 mod dev;
 pub mod ecs;
+mod layers;
 mod physics;
+pub mod snapshot;
 mod system;
-mod timer;
+pub mod timer;
 pub mod ysort;
 
 use crate::prelude::*;
@@ -13,13 +14,22 @@ use crate::prelude::*;
 pub use {
   audio::{Music, SoundEffect, music, sound_effect, sound_effect_with},
   debug::{D, in_debug},
-  ecs::{PausableSystems, Pause, Transform2D, Transform2DSystems},
-  physics::{Control, Controller, ControllerInputsSet, ControllerSystemSet},
-  timer::{LazyTimer, RegisterTimer},
+  ecs::{
+    GlobalTransform2D, Interpolation, PausableSystems, Pause, TimeScale,
+    Transform2D, Transform2DSystems, world_to_local,
+  },
+  layers::{Layers, Profile},
+  physics::{
+    Control, Controller, ControllerInputsSet, ControllerSystemSet, Kinematic,
+    KinematicContacts, KinematicHit, OneWay,
+  },
+  snapshot::{RegisterSnapshot, Snapshot, Snapshotted},
+  timer::{LazyTimer, RegisterTimer, TimerFinished},
   ysort::{BACKGROUND_OFFSET, YSort},
 };
 
-pub(crate) use timer::background_timer;
+#[cfg(test)]
+pub(crate) use physics::test_app;
 
 pub fn plugin(app: &mut App) {
   app.add_plugins((
@@ -28,6 +38,8 @@ pub fn plugin(app: &mut App) {
     ecs::plugin,
     physics::plugin,
     audio::plugin,
+    snapshot::plugin,
+    timer::plugin,
     ysort::plugin,
   ));
 
@@ -71,15 +83,3 @@ pub enum Systems {
   /// Systems that prefer to be `Trigger`, but not
   Watch,
 }
-
-#[derive(PhysicsLayer, Default)]
-pub enum Layers {
-  #[default]
-  Default,
-  //
-  PlayerHurtbox,
-  PlayerCollider,
-  // all terrain types, gotta be separate for casters though
-  Terrain,
-  Platform,
-}
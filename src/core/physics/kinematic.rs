@@ -0,0 +1,407 @@
+//! Collide-and-slide mover of kinematic `Controller`s.
+//!
+//! The body is never pushed by the solver, so every tick the motion is cast
+//! against the terrain, cut at the first hit and slid along its surface.
+
+use {
+  super::{Controller, Motion, OneWay, Teleport},
+  crate::prelude::*,
+};
+
+/// Moves the `Controller` with shape casts instead of the physics solver.
+///
+/// `Control::Move` sets the velocity, gravity is added on top of it and the
+/// blocked part of it is dropped, like a dynamic body would do.
+#[derive(Component, Clone, Debug)]
+#[require(Controller, RigidBody::Kinematic, KinematicContacts)]
+pub struct Kinematic {
+  /// layers blocking the body
+  pub mask: LayerMask,
+  /// gap kept to the surfaces, so the casts never start inside them
+  pub skin: f32,
+  /// highest ledge climbed without jumping
+  pub step: f32,
+  /// steepest walkable ground, as the smallest `y` of its normal
+  pub floor: f32,
+  pub gravity_scale: f32,
+  /// collisions resolved per tick
+  pub slides: usize,
+}
+
+impl Default for Kinematic {
+  fn default() -> Self {
+    Self {
+      mask: LayerMask::from([Layers::Terrain, Layers::Platform]),
+      skin: 0.1,
+      step: 4.0,
+      floor: 0.7,
+      gravity_scale: 1.0,
+      slides: 4,
+    }
+  }
+}
+
+/// What the `Kinematic` body touched during the last tick.
+#[derive(Component, Default, Clone, Debug)]
+pub struct KinematicContacts {
+  pub grounded: bool,
+  /// normal of the walkable ground under the body
+  pub ground: Option<Dir2>,
+  pub hits: Vec<KinematicHit>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct KinematicHit {
+  pub entity: Entity,
+  pub point: Vec2,
+  pub normal: Dir2,
+}
+
+/// Motion along the surface with the `normal`, the part into it is dropped.
+fn project(motion: Vec2, normal: Vec2) -> Vec2 {
+  let into = motion.dot(normal);
+  if into < 0.0 { motion - normal * into } else { motion }
+}
+
+struct Mover<'a, 'w, 's> {
+  spatial: &'a SpatialQuery<'w, 's>,
+  one_way: &'a Query<'w, 's, (), With<OneWay>>,
+  collider: &'a Collider,
+  rotation: f32,
+  filter: SpatialQueryFilter,
+  skin: f32,
+}
+
+impl Mover<'_, '_, '_> {
+  /// The first blocking hit moving `distance` along `dir`, one-way
+  /// platforms block only falling bodies from above.
+  fn cast(
+    &self,
+    origin: Vec2,
+    dir: Dir2,
+    distance: f32,
+  ) -> Option<ShapeHitData> {
+    let config = ShapeCastConfig::from_max_distance(distance + self.skin);
+    let mut filter = self.filter.clone();
+    loop {
+      let hit = self.spatial.cast_shape(
+        self.collider,
+        origin,
+        self.rotation,
+        dir,
+        &config,
+        &filter,
+      )?;
+      let passes = self.one_way.contains(hit.entity)
+        && (dir.y >= 0.0 || hit.normal1.y < 0.5 || hit.distance <= 0.0);
+      if !passes {
+        return Some(hit);
+      }
+      filter.excluded_entities.insert(hit.entity);
+    }
+  }
+
+  /// Moves as far as possible toward `motion`, sliding along the hits.
+  fn slide(
+    &self,
+    mut origin: Vec2,
+    mut motion: Vec2,
+    slides: usize,
+    hits: &mut Vec<KinematicHit>,
+  ) -> Vec2 {
+    for _ in 0..slides {
+      let Ok((dir, distance)) = Dir2::new_and_length(motion) else {
+        break;
+      };
+      let Some(hit) = self.cast(origin, dir, distance) else {
+        return origin + motion;
+      };
+
+      let travel = (hit.distance - self.skin).clamp(0.0, distance);
+      origin += dir * travel;
+      if let Ok(normal) = Dir2::new(hit.normal1) {
+        hits.push(KinematicHit {
+          entity: hit.entity,
+          point: hit.point1,
+          normal,
+        });
+      }
+      motion = project(dir * (distance - travel), hit.normal1);
+    }
+    origin
+  }
+
+  /// Walkable ground within `distance` under the `origin`.
+  fn ground(
+    &self,
+    origin: Vec2,
+    distance: f32,
+    floor: f32,
+  ) -> Option<(f32, Dir2)> {
+    let hit = self.cast(origin, Dir2::NEG_Y, distance)?;
+    let normal = Dir2::new(hit.normal1).ok()?;
+    (normal.y >= floor).then_some((hit.distance, normal))
+  }
+
+  /// Climbs onto a ledge lower than `step` in front of the body.
+  fn step_up(
+    &self,
+    origin: Vec2,
+    forward: f32,
+    step: f32,
+    floor: f32,
+  ) -> Option<Vec2> {
+    let dir = if forward > 0.0 { Dir2::X } else { Dir2::NEG_X };
+    let rise = self
+      .cast(origin, Dir2::Y, step)
+      .map_or(step, |hit| (hit.distance - self.skin).max(0.0));
+    let raised = origin + Vec2::Y * rise;
+    if self.cast(raised, dir, forward.abs()).is_some() {
+      return None;
+    }
+
+    let ahead = raised + dir * forward.abs();
+    let hit = self.cast(ahead, Dir2::NEG_Y, rise)?;
+    (hit.normal1.y >= floor)
+      .then(|| ahead - Vec2::Y * (hit.distance - self.skin).max(0.0))
+  }
+}
+
+pub(super) fn slide(
+  mut query: Query<(
+    Entity,
+    &Kinematic,
+    &mut Controller,
+    &mut KinematicContacts,
+    &Collider,
+    &mut Position,
+    &Rotation,
+    &mut Transform2D,
+    &mut LinearVelocity,
+  )>,
+  one_way: Query<(), With<OneWay>>,
+  spatial: SpatialQuery,
+  gravity: Res<Gravity>,
+  time: Res<Time>,
+  mut commands: Commands,
+) {
+  let dt = time.delta_secs();
+  if dt <= 0.0 {
+    return;
+  }
+
+  for (
+    entity,
+    kinematic,
+    mut controller,
+    mut contacts,
+    collider,
+    mut position,
+    rotation,
+    mut transform,
+    mut velocity,
+  ) in query.iter_mut()
+  {
+    let motion = controller
+      .resolve(velocity.0, dt)
+      .unwrap_or(Motion { velocity: velocity.0, ..default() });
+    if let Some(warp) = motion.warp {
+      transform.translation = warp;
+      // read by the next tick before the physics catches up with the transform
+      position.0 = warp;
+      commands.entity(entity).insert(Teleport);
+      velocity.0 = motion.velocity;
+      // the ground is left behind, nothing to snap to next tick
+      *contacts = KinematicContacts::default();
+      continue;
+    }
+
+    let mut target = motion.velocity;
+    if !contacts.grounded || target.y > 0.0 {
+      target += gravity.0 * kinematic.gravity_scale * dt;
+    }
+
+    let mover = Mover {
+      spatial: &spatial,
+      one_way: &one_way,
+      collider,
+      rotation: rotation.as_radians(),
+      filter: SpatialQueryFilter::from_mask(kinematic.mask)
+        .with_excluded_entities([entity]),
+      skin: kinematic.skin,
+    };
+
+    let origin = position.0;
+    let mut hits = vec![];
+    let mut moved =
+      mover.slide(origin, target * dt, kinematic.slides, &mut hits);
+
+    // blocked by a wall while walking, the wall may be a small ledge
+    let forward = target.x * dt;
+    let walled = hits.iter().any(|hit| hit.normal.x * forward < 0.0);
+    if contacts.grounded
+      && walled
+      && let Some(stepped) =
+        mover.step_up(origin, forward, kinematic.step, kinematic.floor)
+    {
+      moved = stepped;
+    }
+
+    // bodies on the ground follow it down the slopes and steps, falling
+    // ones only land within the skin
+    let snap =
+      if contacts.grounded && target.y <= 0.0 { kinematic.step } else { 0.0 };
+    let ground = mover.ground(moved, snap, kinematic.floor);
+    if let Some((distance, _)) = ground
+      && target.y <= 0.0
+    {
+      moved.y -= (distance - kinematic.skin).max(0.0);
+    }
+
+    // the solver moves kinematic bodies by their velocity
+    velocity.0 = (moved - origin) / dt;
+    if motion.offset != Vec2::ZERO {
+      // slid by the transform like the dynamic bodies, not kept as velocity
+      let slid = mover.slide(moved, motion.offset, kinematic.slides, &mut hits);
+      transform.translation += slid - moved;
+    }
+    *contacts = KinematicContacts {
+      grounded: ground.is_some() && target.y <= 0.0,
+      ground: ground.map(|(_, normal)| normal),
+      hits,
+    };
+  }
+}
+
+#[test]
+fn project_slides() {
+  let up = Vec2::Y;
+  assert_eq!(project(Vec2::new(3.0, -2.0), up), Vec2::new(3.0, 0.0));
+  // moving away from the surface is not blocked
+  assert_eq!(project(Vec2::new(3.0, 2.0), up), Vec2::new(3.0, 2.0));
+
+  let slope = Vec2::new(-1.0, 1.0).normalize();
+  let along = project(Vec2::new(2.0, 0.0), slope);
+  assert!(along.dot(slope).abs() < 1e-5);
+  assert!(along.x > 0.0 && along.y > 0.0);
+}
+
+/// Static blocks of `(center, size)` and a body settled on them at `at`.
+#[cfg(test)]
+fn stage(blocks: &[(Vec2, Vec2)], at: Vec2) -> (App, Entity) {
+  let mut app = super::test_app();
+  app.insert_resource(Gravity(Vec2::NEG_Y * 400.0));
+  let world = app.world_mut();
+  for &(center, size) in blocks {
+    world.spawn((
+      RigidBody::Static,
+      Collider::rectangle(size.x, size.y),
+      Profile::Terrain.layers(),
+      Transform2D::from_translation(center),
+    ));
+  }
+  let body = world
+    .spawn((
+      Kinematic::default(),
+      Collider::rectangle(8.0, 8.0),
+      Transform2D::from_translation(at),
+    ))
+    .id();
+  for _ in 0..4 {
+    app.update();
+  }
+  (app, body)
+}
+
+#[cfg(test)]
+fn walk(app: &mut App, speed: f32) {
+  app.add_systems(
+    FixedUpdate,
+    (move |mut query: Query<&mut Controller>| {
+      for mut controller in query.iter_mut() {
+        controller.control(Control::Move(Vec2::X * speed));
+      }
+    })
+    .in_set(ControllerInputsSet),
+  );
+}
+
+#[test]
+fn steps_up() {
+  let floor = (Vec2::new(0.0, -8.0), Vec2::new(256.0, 16.0));
+  // 3 high, lower than the step
+  let ledge = (Vec2::new(48.0, 1.5), Vec2::new(64.0, 3.0));
+  let (mut app, body) = stage(&[floor, ledge], Vec2::new(0.0, 4.05));
+  walk(&mut app, 64.0);
+
+  for _ in 0..64 {
+    app.update();
+  }
+  let position = app.world().get::<Position>(body).unwrap().0;
+  assert!(position.x > 24.0, "stuck at {position}");
+  assert!((position.y - 7.1).abs() < 0.5, "not on the ledge at {position}");
+}
+
+#[test]
+fn snaps_down() {
+  let upper = (Vec2::new(-32.0, -8.0), Vec2::new(64.0, 16.0));
+  let lower = (Vec2::new(64.0, -11.0), Vec2::new(128.0, 16.0));
+  let (mut app, body) = stage(&[upper, lower], Vec2::new(-8.0, 4.05));
+  walk(&mut app, 64.0);
+
+  // follows the step down instead of falling from it
+  for _ in 0..64 {
+    app.update();
+    assert!(app.world().get::<KinematicContacts>(body).unwrap().grounded);
+  }
+  let position = app.world().get::<Position>(body).unwrap().0;
+  assert!(position.x > 24.0);
+  assert!((position.y - 1.1).abs() < 0.5, "not on the ground at {position}");
+}
+
+#[test]
+fn jumps_through_one_way() {
+  let floor = (Vec2::new(0.0, -40.0), Vec2::new(256.0, 16.0));
+  let (mut app, body) = stage(&[floor], Vec2::new(0.0, -27.95));
+  app.world_mut().spawn((
+    RigidBody::Static,
+    OneWay,
+    Collider::rectangle(64.0, 4.0),
+    Profile::Terrain.layers(),
+    Transform2D::default(),
+  ));
+  app.update();
+
+  let mut controller = app.world_mut().get_mut::<Controller>(body).unwrap();
+  controller.control(Control::Move(Vec2::Y * 250.0));
+  for _ in 0..128 {
+    app.update();
+  }
+  // passed it from below and landed on it from above
+  let position = app.world().get::<Position>(body).unwrap().0;
+  assert!((position.y - 6.1).abs() < 0.5, "not on the platform at {position}");
+  assert!(app.world().get::<KinematicContacts>(body).unwrap().grounded);
+}
+
+#[test]
+fn warp_stops() {
+  let floor = (Vec2::new(0.0, -8.0), Vec2::new(256.0, 16.0));
+  let (mut app, body) = stage(&[floor], Vec2::new(0.0, 4.05));
+  assert!(app.world().get::<KinematicContacts>(body).unwrap().grounded);
+
+  // just above the ground, within the snapping distance
+  let target = Vec2::new(64.0, 6.1);
+  let mut controller = app.world_mut().get_mut::<Controller>(body).unwrap();
+  controller.control(Control::Warp(target));
+  app.update();
+  let world = app.world();
+  assert_eq!(world.get::<Transform2D>(body).unwrap().translation, target);
+  assert!(!world.get::<KinematicContacts>(body).unwrap().grounded);
+
+  // falls from there instead of snapping to the ground
+  app.update();
+  app.update();
+  let position = app.world().get::<Position>(body).unwrap().0;
+  assert!((position.x - target.x).abs() < 1e-3 && position.y < target.y);
+  assert!(position.y > target.y - 0.5, "snapped down to {position}");
+}
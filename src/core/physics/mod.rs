@@ -0,0 +1,305 @@
+mod kinematic;
+
+use bevy::ecs::{
+  schedule::{InternedScheduleLabel, ScheduleLabel},
+  system::SystemParam,
+};
+
+use {super::ecs::Teleport, crate::prelude::*};
+
+pub use kinematic::{Kinematic, KinematicContacts, KinematicHit};
+
+pub fn plugin(app: &mut App) {
+  app.register_type::<OneWay>().add_plugins(
+    PhysicsPlugins::default()
+      .with_length_unit(16.0)
+      .with_collision_hooks::<OneWayHooks>(),
+  );
+
+  app
+    .add_systems(OnEnter(Pause(true)), |mut time: ResMut<Time<Physics>>| {
+      time.pause();
+    })
+    .add_systems(OnEnter(Pause(false)), |mut time: ResMut<Time<Physics>>| {
+      time.unpause();
+    });
+
+  app.add_plugins(ControllerPlugin::new(FixedUpdate));
+}
+
+/// Game physics stepped by hand, every update is a single fixed step.
+#[cfg(test)]
+pub(crate) fn test_app() -> App {
+  use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+  let mut app = App::new();
+  app
+    .add_plugins((
+      MinimalPlugins,
+      StatesPlugin,
+      TransformPlugin,
+      super::ecs::plugin,
+      plugin,
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(
+      Time::<Fixed>::default().timestep(),
+    ));
+  app
+}
+
+/// Collides only with bodies landing on it from above.
+///
+/// Needs [`ActiveCollisionHooks::MODIFY_CONTACTS`] on the collider.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct OneWay;
+
+#[derive(SystemParam)]
+struct OneWayHooks<'w, 's> {
+  one_way: Query<'w, 's, (), With<OneWay>>,
+  velocities: Query<'w, 's, &'static LinearVelocity>,
+}
+
+impl CollisionHooks for OneWayHooks<'_, '_> {
+  fn modify_contacts(
+    &self,
+    contacts: &mut ContactPair,
+    _commands: &mut Commands,
+  ) -> bool {
+    // normals point from the first collider to the second one
+    let (up, other) = if self.one_way.contains(contacts.collider1) {
+      (1.0, contacts.body2)
+    } else if self.one_way.contains(contacts.collider2) {
+      (-1.0, contacts.body1)
+    } else {
+      return true;
+    };
+
+    let rising = other
+      .and_then(|body| self.velocities.get(body).ok())
+      .is_some_and(|velocity| velocity.y > 0.0);
+    !rising
+      && contacts.manifolds.iter().all(|manifold| manifold.normal.y * up > 0.5)
+  }
+}
+
+pub struct ControllerPlugin {
+  schedule: InternedScheduleLabel,
+}
+
+impl ControllerPlugin {
+  pub fn new(schedule: impl ScheduleLabel) -> Self {
+    Self { schedule: schedule.intern() }
+  }
+}
+
+impl Plugin for ControllerPlugin {
+  fn build(&self, app: &mut App) {
+    app.configure_sets(
+      self.schedule,
+      (Pipeline::Sensors, ControllerInputsSet, Pipeline::Apply)
+        .chain()
+        .in_set(ControllerSystemSet),
+    );
+    app.configure_sets(
+      self.schedule,
+      ControllerSystemSet
+        .before(PhysicsStepSet::First)
+        .run_if(|time: Res<Time<Physics>>| !time.is_paused()),
+    );
+    app.add_systems(
+      self.schedule,
+      (apply, kinematic::slide).in_set(Pipeline::Apply),
+    );
+  }
+}
+
+#[derive(
+  SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord,
+)]
+pub enum Pipeline {
+  Sensors,
+  Apply,
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ControllerSystemSet;
+
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ControllerInputsSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Control {
+  /// apply linear velocity, the moves of a tick add up
+  Move(Vec2),
+  /// teleport to position and stop there, not interpolated, the warps of a
+  /// tick to different positions cancel out
+  Warp(Vec2),
+  /// slide to position by offset
+  Slide(Vec2),
+  /// instant change of the velocity, mass is ignored
+  Impulse(Vec2),
+  /// change of the velocity per second, lasts for a single tick
+  Force(Vec2),
+  /// set the velocity along the axis, keeping the rest of it
+  SetVelocityAxis(Dir2, f32),
+}
+
+/// Controls of a tick combined together.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+struct Motion {
+  warp: Option<Vec2>,
+  velocity: Vec2,
+  offset: Vec2,
+}
+
+impl Motion {
+  /// Combines the `controls` in a fixed order whatever order they came in:
+  /// the moves replace the velocity with their sum, then the axes are set,
+  /// then the impulses, forces and offsets are added up. A warp drops all
+  /// of them, unless another one goes elsewhere.
+  fn combine(controls: &[Control], velocity: Vec2, dt: f32) -> Self {
+    let mut motion = Self { velocity, ..default() };
+    let moves = controls.iter().filter_map(|control| match *control {
+      Control::Move(velocity) => Some(velocity),
+      _ => None,
+    });
+    if let Some(velocity) = moves.reduce(|sum, velocity| sum + velocity) {
+      motion.velocity = velocity;
+    }
+    let mut warps = controls.iter().filter_map(|control| match *control {
+      Control::Warp(position) => Some(position),
+      _ => None,
+    });
+    motion.warp = warps.next();
+    if let Some(warp) = motion.warp
+      && warps.any(|other| other != warp)
+    {
+      warn!("conflicting warps of a tick, staying in place");
+      motion.warp = None;
+    }
+    for control in controls {
+      if let Control::SetVelocityAxis(axis, speed) = *control {
+        let along = motion.velocity.dot(*axis);
+        motion.velocity += *axis * (speed - along);
+      }
+    }
+    for control in controls {
+      match *control {
+        Control::Impulse(impulse) => motion.velocity += impulse,
+        Control::Force(force) => motion.velocity += force * dt,
+        Control::Slide(offset) => motion.offset += offset,
+        _ => {}
+      }
+    }
+    if motion.warp.is_some() {
+      // dynamic and kinematic bodies both stop at the target
+      motion.velocity = Vec2::ZERO;
+      motion.offset = Vec2::ZERO;
+    }
+    motion
+  }
+}
+
+// todo!> find better name
+/// Body moved by the controls, through the physics solver when dynamic, see
+/// [`Kinematic`] for the collide-and-slide one.
+///
+/// The `RigidBody` is left to the users, so it never depends on which
+/// required component is resolved first.
+#[derive(Component, Default)]
+#[require(LockedAxes::ROTATION_LOCKED, LinearVelocity, Snapshotted)]
+pub struct Controller {
+  /// gathered until the next `Pipeline::Apply`
+  controls: Vec<Control>,
+}
+
+impl Controller {
+  pub fn control(&mut self, action: Control) {
+    self.controls.push(action);
+  }
+
+  pub fn slide(&mut self, slide: Vec2) -> Vec2 {
+    self.control(Control::Slide(slide));
+    slide
+  }
+
+  fn resolve(&mut self, velocity: Vec2, dt: f32) -> Option<Motion> {
+    if self.controls.is_empty() {
+      return None;
+    }
+    let motion = Motion::combine(&self.controls, velocity, dt);
+    self.controls.clear();
+    Some(motion)
+  }
+}
+
+fn apply(
+  mut query: Query<
+    (Entity, &mut Controller, &mut Transform2D, &mut LinearVelocity),
+    Without<Kinematic>,
+  >,
+  time: Res<Time>,
+  mut commands: Commands,
+) {
+  for (entity, mut controller, mut transform, mut velocity) in query.iter_mut()
+  {
+    let Some(motion) = controller.resolve(velocity.0, time.delta_secs()) else {
+      continue;
+    };
+
+    velocity.0 = motion.velocity;
+    transform.translation += motion.offset;
+    if let Some(position) = motion.warp {
+      transform.translation = position;
+      // no smoothing through the whole way
+      commands.entity(entity).insert(Teleport);
+    }
+  }
+}
+
+#[test]
+fn combine_controls() {
+  let dt = 0.5;
+  let controls = [
+    Control::Impulse(Vec2::new(1.0, 0.0)),
+    Control::SetVelocityAxis(Dir2::Y, 4.0),
+    Control::Move(Vec2::new(2.0, -1.0)),
+    Control::Force(Vec2::new(0.0, -2.0)),
+    Control::Slide(Vec2::X),
+    Control::Slide(Vec2::Y),
+  ];
+  let motion = Motion::combine(&controls, Vec2::ZERO, dt);
+  assert_eq!(motion.velocity, Vec2::new(3.0, 3.0));
+  assert_eq!(motion.offset, Vec2::ONE);
+  assert_eq!(motion.warp, None);
+
+  // the order of the calls does not matter
+  let mut reversed = controls;
+  reversed.reverse();
+  assert_eq!(Motion::combine(&reversed, Vec2::ZERO, dt), motion);
+
+  let warp = [Control::Warp(Vec2::ONE), Control::Move(Vec2::Y)];
+  let stopped = Motion::combine(&warp, Vec2::X, dt);
+  assert_eq!(stopped.velocity, Vec2::ZERO);
+  assert_eq!(stopped.warp, Some(Vec2::ONE));
+}
+
+#[test]
+fn combine_conflicts() {
+  let dt = 0.5;
+  let combine = |controls: [Control; 2]| {
+    let motion = Motion::combine(&controls, Vec2::ZERO, dt);
+    let [a, b] = controls;
+    assert_eq!(Motion::combine(&[b, a], Vec2::ZERO, dt), motion);
+    motion
+  };
+
+  let moves = combine([Control::Move(Vec2::X), Control::Move(Vec2::NEG_Y)]);
+  assert_eq!(moves.velocity, Vec2::new(1.0, -1.0));
+
+  let same = combine([Control::Warp(Vec2::ONE), Control::Warp(Vec2::ONE)]);
+  assert_eq!(same.warp, Some(Vec2::ONE));
+  let apart = combine([Control::Warp(Vec2::ONE), Control::Warp(Vec2::X)]);
+  assert_eq!(apart.warp, None);
+}
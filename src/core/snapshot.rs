@@ -0,0 +1,190 @@
+//! Reflected components of the tagged entities, captured into a blob.
+//!
+//! Only the components opted in with `register_snapshot` are captured, the
+//! blob is plain data, so it may be kept for a pause, written into a quick
+//! save or used to reset a room.
+
+use {
+  crate::prelude::*,
+  bevy::reflect::{
+    GetTypeRegistration, TypeRegistry,
+    serde::{TypedReflectDeserializer, TypedReflectSerializer},
+  },
+  serde::{Deserialize, Serialize, de::DeserializeSeed},
+  std::{any::TypeId, collections::BTreeMap},
+};
+
+pub fn plugin(app: &mut App) {
+  app.register_type::<Snapshotted>().init_resource::<SnapshotTypes>();
+}
+
+pub trait RegisterSnapshot {
+  /// `C` has to reflect `Component`
+  fn register_snapshot<C: Component + GetTypeRegistration>(
+    &mut self,
+  ) -> &mut Self;
+}
+
+impl RegisterSnapshot for App {
+  fn register_snapshot<C: Component + GetTypeRegistration>(
+    &mut self,
+  ) -> &mut Self {
+    self.register_type::<C>();
+    let types =
+      self.world_mut().get_resource_or_init::<SnapshotTypes>().into_inner();
+    if !types.0.contains(&TypeId::of::<C>()) {
+      types.0.push(TypeId::of::<C>());
+    }
+    self
+  }
+}
+
+#[derive(Resource, Default)]
+struct SnapshotTypes(Vec<TypeId>);
+
+/// Entity captured by the snapshots.
+///
+/// Found again by its LDtk iid, or else by the entity itself, which is only
+/// good until the entity is despawned.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Snapshotted;
+
+fn key(entity: Entity, iid: Option<&EntityIid>) -> String {
+  match iid {
+    Some(iid) => iid.to_string(),
+    None => format!("#{}", entity.to_bits()),
+  }
+}
+
+/// Components of the snapshotted entities, serialized with `ron`.
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Snapshot {
+  /// entity key -> type path -> component
+  entities: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Snapshot {
+  pub fn capture(world: &mut World) -> Self {
+    let targets = targets(world);
+    let types = world.resource::<SnapshotTypes>().0.clone();
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let mut entities = BTreeMap::new();
+    for (entity, key) in targets {
+      let entity = world.entity(entity);
+      let components: BTreeMap<_, _> = types
+        .iter()
+        .filter_map(|&type_id| capture(entity, type_id, &registry))
+        .collect();
+      if !components.is_empty() {
+        entities.insert(key, components);
+      }
+    }
+    Self { entities }
+  }
+
+  /// Puts the components back, entities missing from either side are left
+  /// as they are.
+  pub fn restore(&self, world: &mut World) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    for (entity, key) in targets(world) {
+      let Some(components) = self.entities.get(&key) else { continue };
+      for (path, value) in components {
+        let Some(registration) = registry.get_with_type_path(path) else {
+          warn!("snapshot of unknown `{path}` component");
+          continue;
+        };
+        let Some(reflect) = registration.data::<ReflectComponent>() else {
+          continue;
+        };
+
+        let value = ron::Deserializer::from_str(value)
+          .map_err(|err| err.code)
+          .and_then(|mut deserializer| {
+            TypedReflectDeserializer::new(registration, &registry)
+              .deserialize(&mut deserializer)
+          });
+        match value {
+          Ok(value) => {
+            let mut entity = world.entity_mut(entity);
+            reflect.insert(&mut entity, value.as_partial_reflect(), &registry);
+          }
+          Err(err) => warn!("broken snapshot of `{path}`: {err}"),
+        }
+      }
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entities.is_empty()
+  }
+}
+
+fn targets(world: &mut World) -> Vec<(Entity, String)> {
+  world
+    .query_filtered::<(Entity, Option<&EntityIid>), With<Snapshotted>>()
+    .iter(world)
+    .map(|(entity, iid)| (entity, key(entity, iid)))
+    .collect()
+}
+
+/// `(type path, ron)` of the component, if the entity has it
+fn capture(
+  entity: EntityRef,
+  type_id: TypeId,
+  registry: &TypeRegistry,
+) -> Option<(String, String)> {
+  let registration = registry.get(type_id)?;
+  let component = registration.data::<ReflectComponent>()?.reflect(entity)?;
+  let path = registration.type_info().type_path();
+
+  let serializer =
+    TypedReflectSerializer::new(component.as_partial_reflect(), registry);
+  match ron::to_string(&serializer) {
+    Ok(ron) => Some((path.to_string(), ron)),
+    Err(err) => {
+      warn!("can't snapshot `{path}`: {err}");
+      None
+    }
+  }
+}
+
+/// Captures the snapshot into the `Snapshot` resource.
+pub fn store(world: &mut World) {
+  let snapshot = Snapshot::capture(world);
+  world.insert_resource(snapshot);
+}
+
+/// Restores the snapshot from the `Snapshot` resource once.
+pub fn restore(world: &mut World) {
+  if let Some(snapshot) = world.remove_resource::<Snapshot>() {
+    snapshot.restore(world);
+  }
+}
+
+#[test]
+fn round_trip() {
+  #[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+  #[reflect(Component)]
+  struct Speed(f32);
+
+  let mut app = App::new();
+  app.add_plugins(plugin).register_snapshot::<Speed>();
+  let world = app.world_mut();
+  let tagged = world.spawn((Snapshotted, Speed(2.0))).id();
+  let untagged = world.spawn(Speed(3.0)).id();
+
+  let snapshot = Snapshot::capture(world);
+  let blob = ron::to_string(&snapshot).unwrap();
+  let snapshot: Snapshot = ron::from_str(&blob).unwrap();
+
+  world.entity_mut(tagged).insert(Speed(0.0));
+  world.entity_mut(untagged).insert(Speed(0.0));
+  snapshot.restore(world);
+  assert_eq!(world.get::<Speed>(tagged), Some(&Speed(2.0)));
+  assert_eq!(world.get::<Speed>(untagged), Some(&Speed(0.0)));
+}
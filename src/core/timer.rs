@@ -1,74 +1,213 @@
 use {
   crate::prelude::*,
-  bevy::{ecs::component::Mutable, reflect::GetTypeRegistration},
+  bevy::{
+    ecs::{component::Mutable, lifecycle::HookContext, world::DeferredWorld},
+    platform::collections::HashSet,
+    reflect::GetTypeRegistration,
+  },
+  std::any::TypeId,
 };
 
+pub use chroma_macros::LazyTimer;
+
+pub fn plugin(app: &mut App) {
+  app
+    .init_schedule(Update)
+    .init_resource::<Timers>()
+    .add_systems(Last, add_pending);
+}
+
 pub trait RegisterTimer {
   fn register_timer<T: LazyTimer>(&mut self) -> &mut Self;
 }
 
 impl RegisterTimer for App {
   fn register_timer<T: LazyTimer>(&mut self) -> &mut Self {
-    self.register_type::<T>();
-    self.add_systems(
-      Update,
-      tick::<T>.in_set(PausableSystems).in_set(Systems::Timers),
-    );
+    self.world_mut().register_timer::<T>();
     self
   }
 }
 
-fn tick<T: LazyTimer>(mut query: Query<&mut T>, time: Res<Time>) {
-  for mut timer in query.iter_mut() {
+impl RegisterTimer for World {
+  fn register_timer<T: LazyTimer>(&mut self) -> &mut Self {
+    let mut timers = self.get_resource_or_init::<Timers>();
+    if timers.registered.insert(TypeId::of::<T>()) {
+      timers.pending.push(add::<T>);
+      self.resource::<AppTypeRegistry>().write().register::<T>();
+      add_pending(self);
+    }
+    self
+  }
+}
+
+/// Registered timer types and the tick systems not in `Update` yet.
+///
+/// Schedules can't take systems while they run, so the timers added
+/// mid-frame get their tick system in `Last` and start on the next frame.
+#[derive(Resource, Default)]
+struct Timers {
+  registered: HashSet<TypeId>,
+  pending: Vec<fn(&mut Schedule)>,
+}
+
+/// Registers the timer the first time it is added, see `LazyTimer` derive.
+pub fn on_add<T: LazyTimer>(mut world: DeferredWorld, _: HookContext) {
+  if world
+    .get_resource::<Timers>()
+    .is_some_and(|timers| timers.registered.contains(&TypeId::of::<T>()))
+  {
+    return;
+  }
+  world.commands().queue(|world: &mut World| {
+    world.register_timer::<T>();
+  });
+}
+
+fn add_pending(world: &mut World) {
+  if world.resource::<Timers>().pending.is_empty() {
+    return;
+  }
+  world.resource_scope(|world, mut timers: Mut<Timers>| {
+    // taken out of the schedules while it runs
+    if let Some(schedule) = world.resource_mut::<Schedules>().get_mut(Update) {
+      for add in timers.pending.drain(..) {
+        add(schedule);
+      }
+    }
+  });
+}
+
+fn add<T: LazyTimer>(schedule: &mut Schedule) {
+  schedule
+    .add_systems(tick::<T>.in_set(PausableSystems).in_set(Systems::Timers));
+}
+
+fn tick<T: LazyTimer>(
+  mut query: Query<(Entity, &mut T)>,
+  time: Res<Time>,
+  mut commands: Commands,
+) {
+  for (entity, mut timer) in query.iter_mut() {
     timer.tick(time.delta());
+    if !timer.just_finished() {
+      continue;
+    }
+
+    commands.trigger(TimerFinished::<T>::new(entity));
+    // observers of the event still see the timer
+    if T::REMOVE {
+      commands.entity(entity).remove::<T>();
+    }
   }
 }
 
-/// Simple background timer
+/// Triggered on the entity each time its `T` timer finishes.
+#[derive(EntityEvent)]
+pub struct TimerFinished<T: LazyTimer> {
+  pub entity: Entity,
+  marker: PhantomData<T>,
+}
+
+impl<T: LazyTimer> TimerFinished<T> {
+  pub fn new(entity: Entity) -> Self {
+    Self { entity, marker: PhantomData }
+  }
+}
+
+/// Simple background timer, usually derived with `#[derive(LazyTimer)]`
 pub trait LazyTimer:
   Component<Mutability = Mutable> + Reflect + GetTypeRegistration
 {
-  fn tick(&mut self, delta: Duration);
+  const MODE: TimerMode;
+  /// Removes the timer once it finishes, see `#[timer(once, remove)]`
+  const REMOVE: bool = false;
 
-  // todo!> add message about `finished`
-  fn just_finished(&self) -> bool;
+  fn timer(&self) -> &Timer;
 
-  fn duration(&self) -> Duration;
+  fn timer_mut(&mut self) -> &mut Timer;
 
-  fn set_duration(&mut self, time: Duration);
-}
+  fn tick(&mut self, delta: Duration) {
+    self.timer_mut().tick(delta);
+  }
 
-// todo!> make derive macro or any better way
-macro_rules! background_timer {
-  ($ident:ident) => {
-    #[derive(Component, Reflect, Clone)]
-    #[reflect(Component)]
-    pub struct $ident(pub Timer);
+  /// Prefer observing `TimerFinished` over polling every frame
+  fn just_finished(&self) -> bool {
+    self.timer().just_finished()
+  }
 
-    impl $ident {
-      pub fn new(secs: f32) -> Self {
-        Self(Timer::from_seconds(secs, TimerMode::Repeating))
-      }
-    }
+  fn duration(&self) -> Duration {
+    self.timer().duration()
+  }
 
-    impl LazyTimer for $ident {
-      fn tick(&mut self, delta: Duration) {
-        self.0.tick(delta);
-      }
+  fn set_duration(&mut self, duration: Duration) {
+    self.timer_mut().set_duration(duration)
+  }
+}
 
-      fn just_finished(&self) -> bool {
-        self.0.just_finished()
-      }
+#[test]
+fn lazy_registration() {
+  #[derive(Reflect, LazyTimer)]
+  struct Countdown(#[timer(once)] Timer);
 
-      fn duration(&self) -> Duration {
-        self.0.duration()
-      }
+  #[derive(Resource, Default)]
+  struct Finished(usize);
 
-      fn set_duration(&mut self, duration: Duration) {
-        self.0.set_duration(duration)
-      }
-    }
+  let mut app = App::new();
+  app
+    .add_plugins(plugin)
+    .init_resource::<Time>()
+    .init_resource::<Finished>()
+    .add_observer(
+      |_: On<TimerFinished<Countdown>>, mut finished: ResMut<Finished>| {
+        finished.0 += 1;
+      },
+    );
+
+  let world = app.world_mut();
+  let timer = world.spawn(Countdown::new(1.0)).id();
+  world.spawn(Countdown::new(1.0));
+  world.flush();
+  let timers = world.resource::<Timers>();
+  assert_eq!(timers.registered.len(), 1);
+  assert!(timers.pending.is_empty());
+  let registry = world.resource::<AppTypeRegistry>();
+  assert!(registry.read().contains(TypeId::of::<Countdown>()));
+
+  let tick = |app: &mut App, secs: f32| {
+    let delta = Duration::from_secs_f32(secs);
+    app.world_mut().resource_mut::<Time>().advance_by(delta);
+    app.update();
   };
+  tick(&mut app, 0.6);
+  let elapsed = app.world().get::<Countdown>(timer).unwrap().0.elapsed_secs();
+  assert!((elapsed - 0.6).abs() < 1e-5);
+  assert_eq!(app.world().resource::<Finished>().0, 0);
+
+  tick(&mut app, 0.6);
+  assert_eq!(app.world().resource::<Finished>().0, 2);
 }
 
-pub(crate) use background_timer;
+#[test]
+fn registered_mid_frame() {
+  #[derive(Reflect, LazyTimer)]
+  struct Fuse(#[timer(once)] Timer);
+
+  let mut app = App::new();
+  app.add_plugins(plugin).init_resource::<Time>().add_systems(
+    Update,
+    |mut commands: Commands, mut spawned: Local<Option<Entity>>| {
+      spawned.get_or_insert_with(|| commands.spawn(Fuse::new(1.0)).id());
+    },
+  );
+
+  app.update();
+  let world = app.world_mut();
+  assert!(world.resource::<Timers>().pending.is_empty());
+  let fuse = world.query::<(Entity, &Fuse)>().single(world).unwrap().0;
+
+  let delta = Duration::from_secs_f32(0.5);
+  app.world_mut().resource_mut::<Time>().advance_by(delta);
+  app.update();
+  let elapsed = app.world().get::<Fuse>(fuse).unwrap().0.elapsed_secs();
+  assert!((elapsed - 0.5).abs() < 1e-5);
+}
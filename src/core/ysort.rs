@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 pub fn plugin(app: &mut App) {
-  app.add_systems(PostUpdate, ysort.before(TransformSystems::Propagate));
+  app.add_systems(PostUpdate, ysort.before(Transform2DSystems::Propagate));
 }
 
 pub const BACKGROUND_OFFSET: f32 = -128.0;
@@ -12,10 +12,13 @@ pub const YSORT_COHESION: f32 = 0.00001;
 pub struct YSort(pub f32);
 
 pub fn ysort(
-  mut transform: Query<(&mut Transform2D, &GlobalTransform, &YSort)>,
+  mut transform: Query<(&mut Transform2D, &GlobalTransform2D, &YSort)>,
 ) {
   for (mut transform, global_transform, YSort(offset)) in transform.iter_mut() {
-    transform.layer =
-      offset - global_transform.translation().y * YSORT_COHESION;
+    let layer = offset - global_transform.translation().y * YSORT_COHESION;
+    // resting entities are left unchanged
+    if transform.layer != layer {
+      transform.layer = layer;
+    }
   }
 }
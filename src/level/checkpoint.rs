@@ -0,0 +1,128 @@
+use crate::{
+  actors::{Player, player::Death},
+  level::ldtk::Levels,
+  prelude::*,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Checkpoint>()
+    .register_type::<SpawnPoint>()
+    .register_ldtk_entity::<CheckpointBundle>("Checkpoint")
+    .add_systems(
+      Update,
+      (
+        start.run_if(not(resource_exists::<SpawnPoint>)),
+        (
+          spawn,
+          touch,
+          highlight.run_if(resource_exists_and_changed::<SpawnPoint>),
+        )
+          .chain(),
+        fall,
+      )
+        .in_set(Systems::Update),
+    )
+    .add_systems(OnExit(Game::Gameplay), |mut commands: Commands| {
+      commands.remove_resource::<SpawnPoint>();
+    });
+}
+
+/// Distance below the level bounds at which the player dies
+pub const KILL_DEPTH: f32 = 64.0;
+
+const ACTIVE: Srgba = YELLOW_300;
+const INACTIVE: Srgba = STONE_500;
+
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct Checkpoint {
+  pub size: Vec2,
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct CheckpointBundle {
+  #[with(checkpoint)]
+  pub checkpoint: Checkpoint,
+}
+
+fn checkpoint(instance: &EntityInstance) -> Checkpoint {
+  Checkpoint { size: Vec2::new(instance.width as f32, instance.height as f32) }
+}
+
+/// Where the player appears after death
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct SpawnPoint {
+  pub level: String,
+  pub position: Vec2,
+}
+
+/// The place the player entered the game is the first spawn point
+fn start(
+  mut commands: Commands,
+  player: Single<&GlobalTransform2D, With<Player>>,
+  levels: Levels,
+) {
+  let position = player.translation();
+  if let Some((_, level, _)) = levels.at(position) {
+    commands.insert_resource(SpawnPoint { level: level.iid.clone(), position });
+  }
+}
+
+fn spawn(
+  query: Query<(Entity, &Checkpoint), Added<Checkpoint>>,
+  mut commands: Commands,
+) {
+  for (entity, checkpoint) in query.iter() {
+    commands
+      .entity(entity)
+      .insert(Sprite::from_color(INACTIVE, checkpoint.size));
+  }
+}
+
+fn touch(
+  mut commands: Commands,
+  checkpoints: Query<(&Checkpoint, &GlobalTransform2D)>,
+  player: Single<&GlobalTransform2D, With<Player>>,
+  spawn_point: Option<Res<SpawnPoint>>,
+  levels: Levels,
+) {
+  for (checkpoint, transform) in checkpoints.iter() {
+    let position = transform.translation();
+    if Rect::from_center_size(position, checkpoint.size)
+      .contains(player.translation())
+      && spawn_point.as_ref().is_none_or(|spawn| spawn.position != position)
+      && let Some((_, level, _)) = levels.at(position)
+    {
+      commands
+        .insert_resource(SpawnPoint { level: level.iid.clone(), position });
+    }
+  }
+}
+
+fn highlight(
+  spawn_point: Res<SpawnPoint>,
+  mut checkpoints: Query<(&mut Sprite, &GlobalTransform2D), With<Checkpoint>>,
+) {
+  for (mut sprite, transform) in checkpoints.iter_mut() {
+    let active = transform.translation() == spawn_point.position;
+    sprite.color = if active { ACTIVE } else { INACTIVE }.into();
+  }
+}
+
+/// Kill plane below the bounds of the current level
+fn fall(
+  player: Single<&GlobalTransform2D, With<Player>>,
+  levels: Levels,
+  selection: Res<LevelSelection>,
+  mut deaths: MessageWriter<Death>,
+) {
+  let position = player.translation();
+  if levels.at(position).is_none()
+    && let Some((.., bounds)) = levels.selected(&selection)
+    && position.y < bounds.min.y - KILL_DEPTH
+  {
+    deaths.write(Death);
+  }
+}
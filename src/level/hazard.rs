@@ -0,0 +1,130 @@
+//! Spikes, lava and kill zones hurting the player on contact.
+//!
+//! Hazard tiles are merged into sensors along with the walls,
+//! hazard entities are sized in LDtk.
+
+use crate::{
+  actors::{Player, player::Health},
+  level::ldtk::{MapFields, RegisterLdtkFields},
+  prelude::*,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Hazard>()
+    .register_ldtk_entity::<HazardBundle>("Hazard")
+    .register_ldtk_fields::<Hazard>("Hazard")
+    .add_systems(
+      Update,
+      (
+        spawn.after(MapFields).in_set(Systems::Spawn),
+        touch.in_set(Systems::Update),
+      ),
+    );
+}
+
+const SPIKES_DAMAGE: f32 = 1.0;
+
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HazardKind {
+  /// hurts when approached from the `facing` side
+  #[default]
+  Spikes,
+  Lava,
+  /// invisible, usually a pit
+  KillZone,
+}
+
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Facing {
+  #[default]
+  Up,
+  Down,
+  Left,
+  Right,
+}
+
+#[derive(
+  Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+#[reflect(Component, Default)]
+#[require(
+  Sensor,
+  CollisionEventsEnabled,
+  CollisionLayers = Profile::Hazard.layers()
+)]
+pub struct Hazard {
+  pub kind: HazardKind,
+  /// only matters for spikes
+  pub facing: Facing,
+}
+
+impl Hazard {
+  /// Whether the `body` came from the pointed side of the hazard `bounds`
+  fn pierces(&self, bounds: Rect, body: Rect) -> bool {
+    let center = body.center();
+    match self.facing {
+      Facing::Up => center.y > bounds.max.y,
+      Facing::Down => center.y < bounds.min.y,
+      Facing::Left => center.x < bounds.min.x,
+      Facing::Right => center.x > bounds.max.x,
+    }
+  }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct HazardBundle {
+  #[with(collider)]
+  pub collider: Collider,
+  #[from_entity_instance]
+  entity_instance: EntityInstance,
+}
+
+/// `kind` and `facing` come from the LDtk fields
+fn collider(instance: &EntityInstance) -> Collider {
+  Collider::rectangle(instance.width as f32, instance.height as f32)
+}
+
+fn spawn(
+  query: Query<(Entity, &Hazard, &EntityInstance), Added<Hazard>>,
+  mut commands: Commands,
+) {
+  for (entity, hazard, instance) in query.iter() {
+    let color = match hazard.kind {
+      HazardKind::Spikes => STONE_400,
+      HazardKind::Lava => ORANGE_600,
+      HazardKind::KillZone => continue,
+    };
+    let size = Vec2::new(instance.width as f32, instance.height as f32);
+    commands.entity(entity).insert(Sprite::from_color(color, size));
+  }
+}
+
+fn touch(
+  mut start: MessageReader<CollisionStart>,
+  hazards: Query<(&Hazard, &ColliderAabb)>,
+  player: Single<(Entity, &ColliderAabb, &mut Health), With<Player>>,
+) {
+  let (player, body, mut health) = player.into_inner();
+
+  for &CollisionStart { collider1, collider2, .. } in start.read() {
+    let other = if collider1 == player {
+      collider2
+    } else if collider2 == player {
+      collider1
+    } else {
+      continue;
+    };
+    let Ok((hazard, bounds)) = hazards.get(other) else { continue };
+
+    let rect = |aabb: &ColliderAabb| Rect::from_corners(aabb.min, aabb.max);
+    match hazard.kind {
+      HazardKind::Spikes => {
+        if hazard.pierces(rect(bounds), rect(body)) {
+          health.0 -= SPIKES_DAMAGE;
+        }
+      }
+      HazardKind::Lava | HazardKind::KillZone => health.0 = 0.0,
+    }
+  }
+}
@@ -47,7 +47,7 @@ impl From<&EntityInstance> for ColliderBundle {
   }
 }
 
-#[derive(Clone, Default, Bundle, LdtkIntCell)]
+#[derive(Clone, Default, Bundle)]
 pub struct SensorBundle {
   pub collider: Collider,
   pub sensor: Sensor,
@@ -55,20 +55,13 @@ pub struct SensorBundle {
   pub rotation_constraints: LockedAxes,
 }
 
-impl From<IntGridCell> for SensorBundle {
-  fn from(int_grid_cell: IntGridCell) -> SensorBundle {
-    let rotation_constraints = LockedAxes::ROTATION_LOCKED;
-
-    // ladder - todo! avoid hardcode
-    if int_grid_cell.value == 2 {
-      SensorBundle {
-        collider: Collider::rectangle(16., 16.),
-        sensor: Sensor,
-        rotation_constraints,
-        events: CollisionEventsEnabled,
-      }
-    } else {
-      SensorBundle::default()
+impl SensorBundle {
+  pub fn new(size: Vec2) -> Self {
+    SensorBundle {
+      collider: Collider::rectangle(size.x, size.y),
+      sensor: Sensor,
+      rotation_constraints: LockedAxes::ROTATION_LOCKED,
+      events: CollisionEventsEnabled,
     }
   }
 }
@@ -0,0 +1,324 @@
+//! Fills reflected components from the LDtk field instances by name.
+//!
+//! `maxHealth` or `Max_health` fields of LDtk go to `max_health` field of
+//! the component. Entities must keep their [`EntityInstance`], mapped
+//! components are patched if already inserted by the bundle.
+
+use {
+  crate::prelude::*,
+  bevy::reflect::{
+    DynamicEnum, DynamicList, DynamicVariant, GetTypeRegistration, ReflectMut,
+    TypeInfo, TypeRegistry,
+  },
+  std::any::TypeId,
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .init_resource::<FieldMappers>()
+    .add_systems(Update, map.in_set(MapFields).before(Systems::Spawn));
+}
+
+/// Components are filled from LDtk fields, order after it to see them.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MapFields;
+
+pub trait RegisterLdtkFields {
+  /// `C` must reflect `Component` and `Default`
+  fn register_ldtk_fields<C>(&mut self, identifier: &str) -> &mut Self
+  where
+    C: Component + Reflect + GetTypeRegistration;
+}
+
+impl RegisterLdtkFields for App {
+  fn register_ldtk_fields<C>(&mut self, identifier: &str) -> &mut Self
+  where
+    C: Component + Reflect + GetTypeRegistration,
+  {
+    self.register_type::<C>();
+    self
+      .world_mut()
+      .get_resource_or_init::<FieldMappers>()
+      .0
+      .entry(identifier.to_string())
+      .or_default()
+      .push(TypeId::of::<C>());
+    self
+  }
+}
+
+/// LDtk entity identifier -> mapped components
+#[derive(Resource, Default)]
+struct FieldMappers(HashMap<String, Vec<TypeId>>);
+
+fn map(
+  query: Query<(Entity, &EntityInstance), Added<EntityInstance>>,
+  mappers: Res<FieldMappers>,
+  mut commands: Commands,
+) {
+  for (entity, instance) in query.iter() {
+    let Some(components) = mappers.0.get(&instance.identifier) else {
+      continue;
+    };
+    let (instance, components) = (instance.clone(), components.clone());
+    commands.entity(entity).queue(move |mut entity: EntityWorldMut| {
+      let registry = entity.world().resource::<AppTypeRegistry>().clone();
+      apply(&mut entity, &instance, &components, &registry.read());
+    });
+  }
+}
+
+fn apply(
+  entity: &mut EntityWorldMut,
+  instance: &EntityInstance,
+  components: &[TypeId],
+  registry: &TypeRegistry,
+) {
+  let mut matched = HashSet::new();
+
+  for &type_id in components {
+    let Some(registration) = registry.get(type_id) else { continue };
+    let path = registration.type_info().type_path();
+    let (Some(component), Some(default)) = (
+      registration.data::<ReflectComponent>(),
+      registration.data::<ReflectDefault>(),
+    ) else {
+      warn!("`{path}` must reflect `Component` and `Default`");
+      continue;
+    };
+
+    if !component.contains(&*entity) {
+      let value = default.default();
+      component.insert(entity, value.as_partial_reflect(), registry);
+    }
+    let Some(mut reflected) = component.reflect_mut(&mut *entity) else {
+      continue;
+    };
+    let ReflectMut::Struct(target) = reflected.reflect_mut() else {
+      warn!("`{path}` is not a struct");
+      continue;
+    };
+
+    for field in &instance.field_instances {
+      let Some(target) = target.field_mut(&snake_case(&field.identifier))
+      else {
+        continue;
+      };
+      matched.insert(&field.identifier);
+
+      if let Err(err) = set(target, &field.value) {
+        warn!(
+          "`{}.{}` doesn't fit `{path}`: {err}",
+          instance.identifier, field.identifier
+        );
+      }
+    }
+  }
+
+  for field in &instance.field_instances {
+    if !matched.contains(&field.identifier) {
+      warn!(
+        "`{}.{}` has no matching component field",
+        instance.identifier, field.identifier
+      );
+    }
+  }
+}
+
+/// `maxHealth`, `Max_health` -> `max_health`
+fn snake_case(identifier: &str) -> String {
+  let mut name = String::with_capacity(identifier.len() + 4);
+  let mut prev = '_';
+  for char in identifier.chars() {
+    if char.is_uppercase() && prev != '_' && !prev.is_uppercase() {
+      name.push('_');
+    }
+    name.extend(char.to_lowercase());
+    prev = char;
+  }
+  name
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Value<'a> {
+  Int(i32),
+  Float(f32),
+  Bool(bool),
+  Str(&'a str),
+  Color(Color),
+  Point(IVec2),
+}
+
+/// Array fields are lists, null values leave the default
+fn values(value: &FieldValue) -> (bool, Vec<Value<'_>>) {
+  fn all<'a, T>(
+    values: &'a [T],
+    f: impl Fn(&'a T) -> Option<Value<'a>>,
+  ) -> (bool, Vec<Value<'a>>) {
+    (true, values.iter().filter_map(f).collect())
+  }
+  fn one(value: Option<Value>) -> (bool, Vec<Value>) {
+    (false, value.into_iter().collect())
+  }
+
+  match value {
+    FieldValue::Int(v) => one(v.map(Value::Int)),
+    FieldValue::Float(v) => one(v.map(Value::Float)),
+    FieldValue::Bool(v) => one(Some(Value::Bool(*v))),
+    FieldValue::String(v) | FieldValue::FilePath(v) | FieldValue::Enum(v) => {
+      one(v.as_deref().map(Value::Str))
+    }
+    FieldValue::Color(v) => one(Some(Value::Color(*v))),
+    FieldValue::Point(v) => one(v.map(Value::Point)),
+    FieldValue::EntityRef(v) => {
+      one(v.as_ref().map(|v| Value::Str(&v.entity_iid)))
+    }
+    FieldValue::Tile(_) | FieldValue::Tiles(_) => (false, vec![]),
+    FieldValue::Ints(v) => all(v, |v| v.map(Value::Int)),
+    FieldValue::Floats(v) => all(v, |v| v.map(Value::Float)),
+    FieldValue::Bools(v) => all(v, |&v| Some(Value::Bool(v))),
+    FieldValue::Strings(v)
+    | FieldValue::FilePaths(v)
+    | FieldValue::Enums(v) => all(v, |v| v.as_deref().map(Value::Str)),
+    FieldValue::Colors(v) => all(v, |&v| Some(Value::Color(v))),
+    FieldValue::Points(v) => all(v, |v| v.map(Value::Point)),
+    FieldValue::EntityRefs(v) => {
+      all(v, |v| v.as_ref().map(|v| Value::Str(&v.entity_iid)))
+    }
+  }
+}
+
+fn set(target: &mut dyn PartialReflect, value: &FieldValue) -> Result {
+  let info =
+    target.get_represented_type_info().ok_or("field has no type info")?;
+
+  match values(value) {
+    (false, values) => match values.first() {
+      Some(&value) => target.try_apply(&*convert(value, info)?)?,
+      // null
+      None => {}
+    },
+    (true, values) => {
+      let item = info.as_list()?.item_info().ok_or("list of unknown items")?;
+
+      let mut list = DynamicList::default();
+      for value in values {
+        list.push_box(convert(value, item)?);
+      }
+      if let ReflectMut::List(target) = target.reflect_mut() {
+        target.drain();
+      }
+      target.try_apply(&list)?;
+    }
+  }
+  Ok(())
+}
+
+fn convert(
+  value: Value,
+  info: &'static TypeInfo,
+) -> Result<Box<dyn PartialReflect>> {
+  // out of range integers are rejected, not wrapped
+  macro_rules! int {
+    ($value:expr => $($ty:ty),*) => {
+      $(if info.is::<$ty>() {
+        let value = <$ty>::try_from($value).map_err(|_| {
+          format!("`{}` doesn't fit `{}`", $value, info.type_path())
+        })?;
+        return Ok(Box::new(value));
+      })*
+    };
+  }
+  macro_rules! float {
+    ($value:expr => $($ty:ty),*) => {
+      $(if info.is::<$ty>() {
+        return Ok(Box::new($value as $ty));
+      })*
+    };
+  }
+
+  match value {
+    Value::Int(v) => {
+      int!(v => i32, i64, u32, u64, usize, u8, u16);
+      float!(v => f32, f64);
+    }
+    Value::Float(v) => float!(v => f32, f64),
+    Value::Bool(v) if info.is::<bool>() => return Ok(Box::new(v)),
+    Value::Color(v) if info.is::<Color>() => return Ok(Box::new(v)),
+    Value::Point(v) if info.is::<IVec2>() => return Ok(Box::new(v)),
+    Value::Point(v) if info.is::<Vec2>() => return Ok(Box::new(v.as_vec2())),
+    Value::Str(v) if info.is::<String>() => {
+      return Ok(Box::new(v.to_string()));
+    }
+    // LDtk enum values may contain `_`, which is not idiomatic in Rust
+    Value::Str(v) => {
+      let id = v.replace('_', "");
+      if let Ok(enum_info) = info.as_enum()
+        && let Some(&variant) = enum_info
+          .variant_names()
+          .iter()
+          .find(|name| name.eq_ignore_ascii_case(&id))
+      {
+        let mut value = DynamicEnum::new(variant, DynamicVariant::Unit);
+        value.set_represented_type(Some(info));
+        return Ok(Box::new(value));
+      }
+    }
+    _ => {}
+  }
+  Err(format!("`{value:?}` is not a `{}`", info.type_path()).into())
+}
+
+#[test]
+fn names() {
+  assert_eq!(snake_case("locked"), "locked");
+  assert_eq!(snake_case("maxHealth"), "max_health");
+  assert_eq!(snake_case("Max_health"), "max_health");
+  assert_eq!(snake_case("HP"), "hp");
+}
+
+#[test]
+fn fills_fields() {
+  use bevy::reflect::Struct;
+
+  #[derive(Reflect, Default, Debug, PartialEq)]
+  enum Mood {
+    #[default]
+    Calm,
+    VeryAngry,
+  }
+
+  #[derive(Reflect, Default, Debug, PartialEq)]
+  struct Fields {
+    count: u32,
+    speed: f32,
+    mood: Mood,
+    target: String,
+    path: Vec<i64>,
+  }
+
+  let mut fields = Fields { path: vec![7], ..default() };
+  let mut fill = |name, value| set(fields.field_mut(name).unwrap(), &value);
+  fill("count", FieldValue::Int(Some(3))).unwrap();
+  fill("speed", FieldValue::Int(Some(2))).unwrap();
+  fill("mood", FieldValue::Enum(Some("Very_angry".into()))).unwrap();
+  let target = ldtk::EntityRef { entity_iid: "a1".into(), ..default() };
+  fill("target", FieldValue::EntityRef(Some(target))).unwrap();
+  fill("path", FieldValue::Ints(vec![Some(1), None, Some(-2)])).unwrap();
+  // null leaves the value
+  fill("count", FieldValue::Int(None)).unwrap();
+  // negative values don't wrap around
+  assert!(fill("count", FieldValue::Int(Some(-1))).is_err());
+  assert!(fill("mood", FieldValue::Enum(Some("Sad".into()))).is_err());
+
+  assert_eq!(
+    fields,
+    Fields {
+      count: 3,
+      speed: 2.0,
+      mood: Mood::VeryAngry,
+      target: "a1".into(),
+      path: vec![1, -2],
+    }
+  );
+}
@@ -1,9 +1,18 @@
 mod collider;
+mod mapper;
+mod tiles;
 mod walls;
 
-use crate::{actors::Player, prelude::*};
+use {
+  crate::{actors::Player, prelude::*},
+  bevy::ecs::system::SystemParam,
+};
 
-pub use collider::{ColliderBundle, SensorBundle};
+pub use {
+  collider::{ColliderBundle, SensorBundle},
+  mapper::{MapFields, RegisterLdtkFields},
+  walls::WallGrid,
+};
 
 pub fn plugin(app: &mut App) {
   app
@@ -19,40 +28,60 @@ pub fn plugin(app: &mut App) {
     })
     .add_systems(Update, update_level_selection);
 
-  app.add_plugins(walls::plugin);
+  app.add_plugins((mapper::plugin, tiles::plugin, walls::plugin));
+}
+
+/// Spawned levels with their bounds in world space
+#[derive(SystemParam)]
+pub struct Levels<'w, 's> {
+  levels: Query<
+    'w,
+    's,
+    (Entity, &'static LevelIid, &'static GlobalTransform2D),
+    Without<Player>,
+  >,
+  ldtk: Query<'w, 's, &'static LdtkProjectHandle>,
+  ldtk_assets: Res<'w, Assets<LdtkProject>>,
+}
+
+impl Levels<'_, '_> {
+  pub fn iter(&self) -> impl Iterator<Item = (Entity, &ldtk::Level, Rect)> {
+    let ldtk_project =
+      self.ldtk.single().ok().and_then(|ldtk| self.ldtk_assets.get(ldtk));
+
+    self.levels.iter().filter_map(move |(entity, level_iid, transform)| {
+      let level = ldtk_project?.get_raw_level_by_iid(&level_iid.to_string())?;
+      let min = transform.translation();
+      let size = Vec2::new(level.px_wid as f32, level.px_hei as f32);
+      Some((entity, level, Rect::from_corners(min, min + size)))
+    })
+  }
+
+  /// Level that contains the `point`
+  pub fn at(&self, point: Vec2) -> Option<(Entity, &ldtk::Level, Rect)> {
+    self.iter().find(|(.., bounds)| bounds.contains(point))
+  }
+
+  pub fn selected(
+    &self,
+    selection: &LevelSelection,
+  ) -> Option<(Entity, &ldtk::Level, Rect)> {
+    self
+      .iter()
+      .find(|(_, level, _)| selection.is_match(&LevelIndices::default(), level))
+  }
 }
 
 fn update_level_selection(
-  levels: Query<(&LevelIid, &Transform), Without<Player>>,
-  players: Query<&Transform, With<Player>>,
+  levels: Levels,
+  players: Query<&GlobalTransform2D, With<Player>>,
   mut selection: ResMut<LevelSelection>,
-  ldtk: Single<&LdtkProjectHandle>,
-  ldtk_assets: Res<Assets<LdtkProject>>,
 ) {
-  for (level_iid, Transform { translation, .. }) in &levels {
-    if let Some(ldtk) = ldtk_assets.get(*ldtk) {
-      let level = ldtk
-        .get_raw_level_by_iid(&level_iid.to_string())
-        .expect("Spawned level should exist in LDtk project");
-
-      let bounds = Rect {
-        min: Vec2::new(translation.x, translation.y),
-        max: Vec2::new(
-          translation.x + level.px_wid as f32,
-          translation.y + level.px_hei as f32,
-        ),
-      };
-
-      for Transform { translation, .. } in &players {
-        if translation.x < bounds.max.x
-          && translation.x > bounds.min.x
-          && translation.y < bounds.max.y
-          && translation.y > bounds.min.y
-          && !selection.is_match(&LevelIndices::default(), level)
-        {
-          *selection = LevelSelection::iid(level.iid.clone());
-        }
-      }
+  for player in &players {
+    if let Some((_, level, _)) = levels.at(player.translation())
+      && !selection.is_match(&LevelIndices::default(), level)
+    {
+      *selection = LevelSelection::iid(level.iid.clone());
     }
   }
 }
@@ -0,0 +1,203 @@
+//! IntGrid values resolved by their LDtk identifiers.
+//!
+//! Values are just indices in the project file and can be reordered there,
+//! so the behaviour is bound to the identifier and looked up once per load.
+
+use {
+  super::{SensorBundle, walls::Wall},
+  crate::{
+    level::hazard::{Facing, Hazard, HazardKind},
+    prelude::*,
+  },
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Tile>()
+    .init_resource::<TileRegistry>()
+    .add_systems(Update, (build, tag).chain().before(Systems::Spawn));
+}
+
+/// Surface of the solid tiles, merged colliders never mix materials
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Surface {
+  Dirt,
+  Stone,
+  Wood,
+}
+
+impl Surface {
+  pub fn friction(self) -> f32 {
+    match self {
+      Surface::Dirt => 1.0,
+      Surface::Stone => 0.7,
+      Surface::Wood => 0.9,
+    }
+  }
+}
+
+/// Heights of the tile edges in half tiles: `0..=2`.
+///
+/// Full height difference is a 45° slope, half of it is a 22.5° one,
+/// which takes two tiles: `low` and `high`.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Slope {
+  pub left: u8,
+  pub right: u8,
+}
+
+impl Slope {
+  /// Convex collider of the tile centered at the origin
+  pub fn collider(self, grid_size: f32) -> Collider {
+    let half = grid_size / 2.0;
+    let height = |edge: u8| edge as f32 * half - half;
+    let mut points = vec![Vec2::new(-half, -half), Vec2::new(half, -half)];
+    points.push(Vec2::new(half, height(self.right)));
+    points.push(Vec2::new(-half, height(self.left)));
+    points.dedup();
+
+    Collider::convex_hull(points).expect("slope tile is not degenerate")
+  }
+}
+
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub enum Tile {
+  /// merged into wall colliders, `one_way` ones are passed from below
+  Solid { surface: Surface, one_way: bool },
+  /// single tile slope, not merged
+  Slope(Slope),
+  /// merged into sensors
+  Hazard(Hazard),
+  /// climbable sensor
+  Ladder,
+}
+
+impl Tile {
+  pub fn from_identifier(identifier: &str) -> Option<Self> {
+    let solid = |surface| Self::Solid { surface, one_way: false };
+    let spikes =
+      |facing| Self::Hazard(Hazard { kind: HazardKind::Spikes, facing });
+    Some(match identifier {
+      "dirt" => solid(Surface::Dirt),
+      "stone" => solid(Surface::Stone),
+      "platform" => Self::Solid { surface: Surface::Wood, one_way: true },
+      "ladder" => Self::Ladder,
+      "slope_up" => Self::Slope(Slope { left: 0, right: 2 }),
+      "slope_down" => Self::Slope(Slope { left: 2, right: 0 }),
+      "slope_up_low" => Self::Slope(Slope { left: 0, right: 1 }),
+      "slope_up_high" => Self::Slope(Slope { left: 1, right: 2 }),
+      "slope_down_low" => Self::Slope(Slope { left: 1, right: 0 }),
+      "slope_down_high" => Self::Slope(Slope { left: 2, right: 1 }),
+      "spikes_up" => spikes(Facing::Up),
+      "spikes_down" => spikes(Facing::Down),
+      "spikes_left" => spikes(Facing::Left),
+      "spikes_right" => spikes(Facing::Right),
+      "lava" => Self::Hazard(Hazard { kind: HazardKind::Lava, ..default() }),
+      _ => return None,
+    })
+  }
+
+  /// Whether enemies can walk on it
+  pub fn is_solid(self) -> bool {
+    matches!(self, Self::Solid { .. } | Self::Slope(_))
+  }
+
+  /// Whether it is merged into the wall colliders
+  pub fn is_wall(self) -> bool {
+    !matches!(self, Self::Ladder)
+  }
+}
+
+/// Collision profile of the walls merged from an IntGrid layer
+fn profile(identifier: &str) -> Profile {
+  match identifier {
+    "Platforms" => Profile::Platform,
+    _ => Profile::Terrain,
+  }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct TileRegistry {
+  /// `(layer identifier, value)` -> tile
+  tiles: HashMap<(String, i32), Tile>,
+  /// layer identifier -> collision profile
+  layers: HashMap<String, Profile>,
+}
+
+impl TileRegistry {
+  pub fn get(&self, layer: &str, value: i32) -> Option<Tile> {
+    self.tiles.get(&(layer.to_string(), value)).copied()
+  }
+
+  /// Whether the layer has any IntGrid values
+  pub fn is_collision(&self, layer: &str) -> bool {
+    self.layers.contains_key(layer)
+  }
+
+  pub fn profile(&self, layer: &str) -> Profile {
+    self.layers.get(layer).copied().unwrap_or(Profile::Terrain)
+  }
+}
+
+fn build(
+  mut events: MessageReader<AssetEvent<LdtkProject>>,
+  projects: Res<Assets<LdtkProject>>,
+  mut registry: ResMut<TileRegistry>,
+) {
+  for event in events.read() {
+    let (AssetEvent::LoadedWithDependencies { id }
+    | AssetEvent::Modified { id }) = *event
+    else {
+      continue;
+    };
+    let Some(project) = projects.get(id) else { continue };
+
+    registry.tiles.clear();
+    registry.layers.clear();
+    for layer in &project.json_data().defs.layers {
+      if !layer.int_grid_values.is_empty() {
+        let profile = profile(&layer.identifier);
+        registry.layers.insert(layer.identifier.clone(), profile);
+      }
+      for value in &layer.int_grid_values {
+        let identifier = value.identifier.as_deref().unwrap_or_default();
+        match Tile::from_identifier(identifier) {
+          Some(tile) => {
+            let key = (layer.identifier.clone(), value.value);
+            registry.tiles.insert(key, tile);
+          }
+          None => warn!(
+            "unknown IntGrid value `{identifier}` ({}) of `{}` layer",
+            value.value, layer.identifier
+          ),
+        }
+      }
+    }
+  }
+}
+
+pub(super) fn tag(
+  cells: Query<(Entity, &IntGridCell, &ChildOf), Added<IntGridCell>>,
+  layers: Query<&LayerMetadata>,
+  registry: Res<TileRegistry>,
+  mut commands: Commands,
+) {
+  for (entity, cell, child_of) in cells.iter() {
+    let Ok(layer) = layers.get(child_of.parent()) else { continue };
+    let Some(tile) = registry.get(&layer.identifier, cell.value) else {
+      continue;
+    };
+
+    let mut entity = commands.entity(entity);
+    entity.insert(tile);
+    match tile {
+      Tile::Solid { .. } | Tile::Slope(_) | Tile::Hazard(_) => {
+        entity.insert(Wall)
+      }
+      Tile::Ladder => {
+        entity.insert(SensorBundle::new(Vec2::splat(layer.grid_size as f32)))
+      }
+    };
+  }
+}
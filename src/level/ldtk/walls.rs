@@ -1,45 +1,127 @@
-use crate::prelude::*;
+use {
+  super::tiles::{self, Surface, Tile, TileRegistry},
+  crate::prelude::*,
+  bevy::ecs::entity::EntityHashMap,
+  std::ops::Range,
+};
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
 pub struct Wall;
 
-#[derive(Clone, Debug, Default, Bundle, LdtkIntCell)]
-pub struct WallBundle {
-  wall: Wall,
-}
-
-// todo! add ldtk mapper
+/// Walls are tagged by the tile registry, see [`Tile::Solid`]
 pub fn plugin(app: &mut App) {
-  app
-    .add_systems(Update, spawn_wall_collision)
-    .register_ldtk_int_cell::<WallBundle>(1) //dirt
-    .register_ldtk_int_cell::<WallBundle>(3); //stone
+  app.add_message::<EditTile>().add_systems(
+    Update,
+    (
+      paint.run_if(in_debug(D::L1)),
+      (init_grid, edit, spawn_wall_collision).chain().after(tiles::tag),
+    )
+      .chain(),
+  );
 }
 
-/// Spawns heron collisions for the walls of a level
+/// Places (`Some`) or removes (`None`) a wall tile of the `layer` at runtime.
 ///
-/// You could just insert a ColliderBundle into the WallBundle,
-/// but this spawns a different collider for EVERY wall tile.
-/// This approach leads to bad performance.
-///
-/// Instead, by flagging the wall tiles and spawning the collisions later,
-/// we can minimize the amount of colliding entities.
+/// Only the rows around the tile are merged again.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct EditTile {
+  pub layer: Entity,
+  pub coords: GridCoords,
+  pub tile: Option<Tile>,
+}
+
+/// Wall tiles of a layer and the colliders merged from them
+#[derive(Component, Default, Debug)]
+pub struct WallGrid {
+  pub cells: HashMap<GridCoords, Tile>,
+  /// cell -> its tile entity
+  entities: HashMap<GridCoords, Entity>,
+  colliders: Vec<(Entity, Cells)>,
+}
+
+impl WallGrid {
+  fn entity(&self, coords: GridCoords) -> Option<Entity> {
+    self.entities.get(&coords).copied()
+  }
+
+  /// Takes out the colliders crossing the `changed` rows of a `width` wide
+  /// layer, returns them along with the rects merged again in their place.
+  fn remerge(
+    &mut self,
+    changed: Range<i32>,
+    width: i32,
+  ) -> (Vec<Entity>, Vec<(Tile, Cells)>) {
+    // any cell of the changed rows is covered only by such colliders
+    let (stale, kept): (Vec<_>, Vec<_>) =
+      std::mem::take(&mut self.colliders).into_iter().partition(|(_, rect)| {
+        rect.bottom < changed.end && rect.top >= changed.start
+      });
+    self.colliders = kept;
+
+    let mut region: HashSet<GridCoords> = self
+      .cells
+      .keys()
+      .filter(|cell| changed.contains(&cell.y))
+      .copied()
+      .collect();
+    let mut rows = changed;
+    for (_, rect) in &stale {
+      rows = rows.start.min(rect.bottom)..rows.end.max(rect.top + 1);
+      region.extend(
+        (rect.bottom..=rect.top)
+          .flat_map(|y| {
+            (rect.left..=rect.right).map(move |x| GridCoords { x, y })
+          })
+          .filter(|cell| self.cells.contains_key(cell)),
+      );
+    }
+
+    let mut tiles: HashMap<Tile, HashSet<GridCoords>> = HashMap::new();
+    for cell in region {
+      tiles.entry(self.cells[&cell]).or_default().insert(cell);
+    }
+
+    let mut rects = vec![];
+    for (tile, walls) in tiles {
+      match tile {
+        // every slope is a collider of its own
+        Tile::Slope(_) => {
+          rects.extend(walls.iter().map(|&GridCoords { x, y }| {
+            (tile, Cells { left: x, right: x, top: y, bottom: y })
+          }))
+        }
+        _ => rects.extend(
+          merge(&walls, width, rows.clone())
+            .into_iter()
+            .map(|rect| (tile, rect)),
+        ),
+      }
+    }
+    (stale.into_iter().map(|(collider, _)| collider).collect(), rects)
+  }
+}
+
+/// A simple rectangle type representing a wall of any size, in cells
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Cells {
+  pub left: i32,
+  pub right: i32,
+  pub top: i32,
+  pub bottom: i32,
+}
+
+/// Combines wall tiles of a `width` wide layer within `rows` into rectangles.
 ///
 /// The algorithm used here is a nice compromise between simplicity, speed,
 /// and a small number of rectangle colliders.
 /// In basic terms, it will:
-/// 1. consider where the walls are
-/// 2. combine wall tiles into flat "plates" in each individual row
-/// 3. combine the plates into rectangles across multiple rows wherever possible
-/// 4. spawn colliders for each rectangle
-pub fn spawn_wall_collision(
-  mut commands: Commands,
-  walls: Query<(&GridCoords, &ChildOf), Added<Wall>>,
-  parents: Query<&ChildOf, Without<Wall>>,
-  levels: Query<(Entity, &LevelIid)>,
-  ldtk: Single<&LdtkProjectHandle>,
-  ldtk_assets: Res<Assets<LdtkProject>>,
-) {
+/// 1. combine wall tiles into flat "plates" in each individual row
+/// 2. combine the plates into rectangles across multiple rows wherever possible
+pub fn merge(
+  walls: &HashSet<GridCoords>,
+  width: i32,
+  rows: Range<i32>,
+) -> Vec<Cells> {
   /// Represents a wide wall that is 1 tile tall
   /// Used to spawn wall collisions
   #[derive(Clone, Eq, PartialEq, Debug, Default, Hash)]
@@ -48,138 +130,319 @@ pub fn spawn_wall_collision(
     right: i32,
   }
 
-  /// A simple rectangle type representing a wall of any size
-  struct Rect {
-    left: i32,
-    right: i32,
-    top: i32,
-    bottom: i32,
-  }
+  // combine wall tiles into flat "plates" in each individual row
+  let mut plate_stack: Vec<Vec<Plate>> = Vec::new();
 
-  // Consider where the walls are
-  // storing them as GridCoords in a HashSet for quick, easy lookup
-  //
-  // The key of this map will be the entity of the level the wall belongs to.
-  // This has two consequences in the resulting collision entities:
-  // 1. it forces the walls to be split along level boundaries
-  // 2. it lets us easily add the collision entities as children of the appropriate level entity
-  let mut level_to_wall_locations: HashMap<Entity, HashSet<GridCoords>> =
-    HashMap::new();
-
-  walls.iter().for_each(|(&grid_coords, child_of)| {
-    // An intgrid tile's direct parent will be a layer entity, not the level entity
-    // To get the level entity, you need the tile's grandparent.
-    // This is where parent_query comes in.
-    if let Ok(parent_child_of) = parents.get(child_of.parent()) {
-      level_to_wall_locations
-        .entry(parent_child_of.parent())
-        .or_default()
-        .insert(grid_coords);
-    }
-  });
-
-  if !walls.is_empty() {
-    levels.iter().for_each(|(level_entity, level_iid)| {
-      if let Some(level_walls) = level_to_wall_locations.get(&level_entity) {
-        let ldtk_project = ldtk_assets
-          .get(*ldtk)
-          .expect("Project should be loaded if level has spawned");
-
-        let level = ldtk_project
-          .as_standalone()
-          .get_loaded_level_by_iid(&level_iid.to_string())
-          .expect("Spawned level should exist in LDtk project");
-
-        let LayerInstance { c_wid: width, c_hei: height, grid_size, .. } =
-          level.layer_instances()[0];
-
-        // combine wall tiles into flat "plates" in each individual row
-        let mut plate_stack: Vec<Vec<Plate>> = Vec::new();
-
-        for y in 0..height {
-          let mut row_plates: Vec<Plate> = Vec::new();
-          let mut plate_start = None;
-
-          // + 1 to the width so the algorithm "terminates" plates that touch the right edge
-          for x in 0..width + 1 {
-            match (plate_start, level_walls.contains(&GridCoords { x, y })) {
-              (Some(s), false) => {
-                row_plates.push(Plate { left: s, right: x - 1 });
-                plate_start = None;
-              }
-              (None, true) => plate_start = Some(x),
-              _ => (),
-            }
-          }
-
-          plate_stack.push(row_plates);
+  for y in rows.clone() {
+    let mut row_plates: Vec<Plate> = Vec::new();
+    let mut plate_start = None;
+
+    // + 1 to the width so the algorithm "terminates" plates that touch the right edge
+    for x in 0..width + 1 {
+      match (plate_start, walls.contains(&GridCoords { x, y })) {
+        (Some(s), false) => {
+          row_plates.push(Plate { left: s, right: x - 1 });
+          plate_start = None;
         }
+        (None, true) => plate_start = Some(x),
+        _ => (),
+      }
+    }
+
+    plate_stack.push(row_plates);
+  }
+
+  // combine "plates" into rectangles across multiple rows
+  let mut rect_builder: HashMap<Plate, Cells> = HashMap::new();
+  let mut prev_row: Vec<Plate> = Vec::new();
+  let mut wall_rects: Vec<Cells> = Vec::new();
+
+  // an extra empty row so the algorithm "finishes" the rects that touch the top edge
+  plate_stack.push(Vec::new());
 
-        // combine "plates" into rectangles across multiple rows
-        let mut rect_builder: HashMap<Plate, Rect> = HashMap::new();
-        let mut prev_row: Vec<Plate> = Vec::new();
-        let mut wall_rects: Vec<Rect> = Vec::new();
-
-        // an extra empty row so the algorithm "finishes" the rects that touch the top edge
-        plate_stack.push(Vec::new());
-
-        for (y, current_row) in plate_stack.into_iter().enumerate() {
-          for prev_plate in &prev_row {
-            if !current_row.contains(prev_plate) {
-              // remove the finished rect so that the same plate in the future starts a new rect
-              if let Some(rect) = rect_builder.remove(prev_plate) {
-                wall_rects.push(rect);
-              }
-            }
-          }
-          for plate in &current_row {
-            rect_builder
-              .entry(plate.clone())
-              .and_modify(|e| e.top += 1)
-              .or_insert(Rect {
-                bottom: y as i32,
-                top: y as i32,
-                left: plate.left,
-                right: plate.right,
-              });
-          }
-          prev_row = current_row;
+  for (y, current_row) in (rows.start..=rows.end).zip(plate_stack) {
+    for prev_plate in &prev_row {
+      if !current_row.contains(prev_plate) {
+        // remove the finished rect so that the same plate in the future starts a new rect
+        if let Some(rect) = rect_builder.remove(prev_plate) {
+          wall_rects.push(rect);
         }
+      }
+    }
+    for plate in &current_row {
+      rect_builder.entry(plate.clone()).and_modify(|e| e.top += 1).or_insert(
+        Cells { bottom: y, top: y, left: plate.left, right: plate.right },
+      );
+    }
+    prev_row = current_row;
+  }
 
-        commands.entity(level_entity).with_children(|level| {
-          // Spawn colliders for every rectangle.
-          // Making the collider a child of the level serves two purposes:
-          // 1. Adjusts the transforms to be relative to the level for free
-          // 2. the colliders will be despawned automatically when levels unload
-          for wall_rect in wall_rects {
-            level
-              .spawn_empty()
-              .insert(Collider::rectangle(
-                (wall_rect.right as f32 - wall_rect.left as f32 + 1.)
-                  * grid_size as f32,
-                (wall_rect.top as f32 - wall_rect.bottom as f32 + 1.)
-                  * grid_size as f32,
-              ))
-              .insert(RigidBody::Kinematic)
-              .insert(Friction::new(1.0))
-              .insert(Transform2D::from_xy(
-                (wall_rect.left + wall_rect.right + 1) as f32
-                  * grid_size as f32
-                  / 2.,
-                (wall_rect.bottom + wall_rect.top + 1) as f32
-                  * grid_size as f32
-                  / 2.,
-              ))
-              .insert(GlobalTransform::default())
-              // todo! make merged tiles more funny
-              .insert(CollisionLayers::new(
-                Layers::Terrain,
-                [Layers::PlayerCollider],
-              ))
-            ;
-          }
-        });
+  wall_rects
+}
+
+/// Spawns the collider of the merged `rect` as a child of the layer.
+///
+/// Slopes are always a single cell, hazards are sensors, ladders aren't
+/// walls and spawn nothing.
+///
+/// Making the collider a child of the layer serves two purposes:
+/// 1. Adjusts the transforms to be relative to the layer for free
+/// 2. the colliders will be despawned automatically when levels unload
+pub fn spawn_wall(
+  commands: &mut Commands,
+  layer: Entity,
+  rect: Cells,
+  grid_size: f32,
+  tile: Tile,
+  profile: Profile,
+) -> Option<Entity> {
+  let rectangle = Collider::rectangle(
+    (rect.right - rect.left + 1) as f32 * grid_size,
+    (rect.top - rect.bottom + 1) as f32 * grid_size,
+  );
+  let body = (
+    RigidBody::Kinematic,
+    Transform2D::from_xy(
+      (rect.left + rect.right + 1) as f32 * grid_size / 2.,
+      (rect.bottom + rect.top + 1) as f32 * grid_size / 2.,
+    ),
+    GlobalTransform::default(),
+    ChildOf(layer),
+  );
+
+  let (collider, surface, one_way) = match tile {
+    Tile::Solid { surface, one_way } => (rectangle, surface, one_way),
+    Tile::Slope(slope) => (slope.collider(grid_size), Surface::Dirt, false),
+    // the layers come with the hazard
+    Tile::Hazard(hazard) => {
+      return Some(commands.spawn((body, rectangle, hazard)).id());
+    }
+    // ladders are sensors of their own, see `tiles::tag`
+    Tile::Ladder => return None,
+  };
+  let mut wall = commands.spawn(body);
+  wall.insert((collider, Friction::new(surface.friction())));
+
+  // one way tiles are platforms whatever layer they are on
+  if one_way {
+    wall.insert((
+      OneWay,
+      ActiveCollisionHooks::MODIFY_CONTACTS,
+      Profile::Platform.layers(),
+    ));
+  } else {
+    wall.insert(profile.layers());
+  }
+  Some(wall.id())
+}
+
+fn init_grid(
+  layers: Query<Entity, Added<LayerMetadata>>,
+  mut commands: Commands,
+) {
+  for layer in layers.iter() {
+    commands.entity(layer).insert(WallGrid::default());
+  }
+}
+
+/// Right click toggles a dirt tile under the cursor
+fn paint(
+  mouse: Res<ButtonInput<MouseButton>>,
+  window: Single<&Window, With<PrimaryWindow>>,
+  camera: Single<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+  layers: Query<(Entity, &LayerMetadata, &GlobalTransform2D, &WallGrid)>,
+  registry: Res<TileRegistry>,
+  mut edits: MessageWriter<EditTile>,
+) {
+  if !mouse.just_pressed(MouseButton::Right) {
+    return;
+  }
+  let (camera, camera_transform) = *camera;
+  let Some(point) = window.cursor_position().and_then(|cursor| {
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+  }) else {
+    return;
+  };
+
+  for (layer, metadata, transform, grid) in layers.iter() {
+    let cell = (transform.inverse_transform_point(point)
+      / metadata.grid_size as f32)
+      .floor()
+      .as_ivec2();
+    let size = IVec2::new(metadata.c_wid, metadata.c_hei);
+    if !registry.is_collision(&metadata.identifier)
+      || cell.cmplt(IVec2::ZERO).any()
+      || cell.cmpge(size).any()
+    {
+      continue;
+    }
+
+    let coords = GridCoords::new(cell.x, cell.y);
+    let tile = (!grid.cells.contains_key(&coords))
+      .then_some(Tile::Solid { surface: Surface::Dirt, one_way: false });
+    edits.write(EditTile { layer, coords, tile });
+    break;
+  }
+}
+
+/// Edits are applied to tile entities, so they look like LDtk ones
+fn edit(
+  mut edits: MessageReader<EditTile>,
+  grids: Query<&WallGrid>,
+  mut commands: Commands,
+) {
+  for &EditTile { layer, coords, tile } in edits.read() {
+    let Ok(grid) = grids.get(layer) else { continue };
+    if let Some(tile) = tile
+      && !tile.is_wall()
+    {
+      warn!("`{tile:?}` is not a wall tile, the edit is skipped");
+      continue;
+    }
+
+    if let Some(old) = grid.entity(coords) {
+      commands.entity(old).despawn();
+    }
+    if let Some(tile) = tile {
+      commands.spawn((Wall, tile, coords, ChildOf(layer)));
+    }
+  }
+}
+
+/// Spawns collisions for the walls of every layer
+///
+/// You could just insert a ColliderBundle into every wall tile,
+/// but this spawns a different collider for EVERY wall tile.
+/// This approach leads to bad performance.
+///
+/// Instead, by flagging the wall tiles and spawning the collisions later,
+/// we can minimize the amount of colliding entities.
+///
+/// Tiles of different surfaces are merged separately, every layer uses
+/// its own grid size and physics layer.
+///
+/// Changed tiles only rebuild colliders crossing their rows, the rest of
+/// the layer is kept as is.
+pub fn spawn_wall_collision(
+  mut commands: Commands,
+  walls: Query<(Entity, &GridCoords, &ChildOf, &Tile), Added<Wall>>,
+  mut removed: RemovedComponents<Wall>,
+  mut layers: Query<(&LayerMetadata, &mut WallGrid)>,
+  // tile entity -> its layer and cell, as removed tiles lose them
+  mut tiles: Local<EntityHashMap<(Entity, GridCoords)>>,
+  registry: Res<TileRegistry>,
+) {
+  // Consider where the walls are, storing them in the grid of their layer
+  // along with the band of changed rows.
+  // Keying by the layer has two consequences in the resulting colliders:
+  // 1. it forces the walls to be split along level and layer boundaries
+  // 2. it lets us easily add the collision entities as children of the layer
+  let mut dirty: HashMap<Entity, (i32, i32)> = HashMap::new();
+  let mut touch = |layer: Entity, y: i32| {
+    let (bottom, top) = dirty.entry(layer).or_insert((y, y));
+    (*bottom, *top) = ((*bottom).min(y), (*top).max(y));
+  };
+
+  for (entity, &grid_coords, child_of, &tile) in walls.iter() {
+    // An intgrid tile's direct parent is the layer entity
+    let layer = child_of.parent();
+    if let Ok((_, mut grid)) = layers.get_mut(layer) {
+      grid.cells.insert(grid_coords, tile);
+      grid.entities.insert(grid_coords, entity);
+      tiles.insert(entity, (layer, grid_coords));
+      touch(layer, grid_coords.y);
+    }
+  }
+
+  for entity in removed.read() {
+    let Some((layer, coords)) = tiles.remove(&entity) else { continue };
+    // the whole layer is gone if the tile was unloaded with its level
+    let Ok((_, mut grid)) = layers.get_mut(layer) else { continue };
+    // the cell may be taken by a new tile already
+    if grid.entity(coords) == Some(entity) {
+      grid.entities.remove(&coords);
+      grid.cells.remove(&coords);
+    }
+    touch(layer, coords.y);
+  }
+
+  for (layer_entity, (bottom, top)) in dirty {
+    let Ok((layer, mut grid)) = layers.get_mut(layer_entity) else {
+      continue;
+    };
+    let grid_size = layer.grid_size as f32;
+    let profile = registry.profile(&layer.identifier);
+
+    let (stale, rects) = grid.remerge(bottom..top + 1, layer.c_wid);
+    for collider in stale {
+      commands.entity(collider).despawn();
+    }
+    for (tile, rect) in rects {
+      if let Some(collider) =
+        spawn_wall(&mut commands, layer_entity, rect, grid_size, tile, profile)
+      {
+        grid.colliders.push((collider, rect));
       }
-    });
+    }
   }
 }
+
+#[test]
+fn merge_rects() {
+  // ...
+  // ##.
+  // ###
+  let walls = [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1)]
+    .map(|(x, y)| GridCoords { x, y })
+    .into_iter()
+    .collect();
+
+  let mut rects = merge(&walls, 3, 0..3);
+  rects.sort_by_key(|rect| rect.bottom);
+  assert_eq!(
+    rects,
+    [
+      Cells { left: 0, right: 2, top: 0, bottom: 0 },
+      Cells { left: 0, right: 1, top: 1, bottom: 1 },
+    ]
+  );
+}
+
+#[test]
+fn remerge_changed_rows() {
+  let solid = Tile::Solid { surface: Surface::Dirt, one_way: false };
+  // #..
+  // ###
+  // ##.
+  let mut grid = WallGrid::default();
+  for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (0, 2)] {
+    grid.cells.insert(GridCoords { x, y }, solid);
+  }
+  let (_, rects) = grid.remerge(0..3, 3);
+  let (bottom, middle, top) =
+    (Entity::from_bits(1), Entity::from_bits(2), Entity::from_bits(3));
+  for (_, rect) in rects {
+    let collider = match rect.bottom {
+      0 => bottom,
+      1 => middle,
+      _ => top,
+    };
+    grid.colliders.push((collider, rect));
+  }
+
+  // ##.
+  // ###
+  // ##.
+  grid.cells.insert(GridCoords { x: 1, y: 2 }, solid);
+  let (stale, rects) = grid.remerge(2..3, 3);
+  // the rows below keep their colliders
+  assert_eq!(stale, [top]);
+  assert_eq!(
+    grid.colliders,
+    [
+      (bottom, Cells { left: 0, right: 1, top: 0, bottom: 0 }),
+      (middle, Cells { left: 0, right: 2, top: 1, bottom: 1 })
+    ]
+  );
+  assert_eq!(rects, [(solid, Cells { left: 0, right: 1, top: 2, bottom: 2 })]);
+}
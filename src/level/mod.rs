@@ -1,13 +1,17 @@
 mod camera;
+pub mod checkpoint;
 mod ground;
+mod hazard;
 pub mod ldtk;
+pub mod nav;
+mod persist;
 
 use crate::{
   actors::{Enemy, Player, enemy},
   prelude::*,
 };
 
-pub use ground::GroundDetector;
+pub use {ground::GroundDetector, persist::RegisterPersistent};
 
 pub fn plugin(app: &mut App) {
   app.register_type::<LevelAssets>();
@@ -17,7 +21,14 @@ pub fn plugin(app: &mut App) {
       .load_collection::<LevelAssets>(),
   );
 
-  app.add_plugins((camera::plugin, ldtk::plugin, ground::plugin));
+  app.add_plugins((
+    camera::plugin,
+    checkpoint::plugin,
+    ldtk::plugin,
+    ground::plugin,
+    hazard::plugin,
+    nav::plugin,
+  ));
 }
 
 // todo!> find better name
@@ -0,0 +1,353 @@
+use {
+  crate::{level::ldtk::WallGrid, prelude::*},
+  bevy::ecs::system::SystemParam,
+  serde::Deserialize,
+  std::{cmp::Reverse, collections::BinaryHeap},
+};
+
+pub fn plugin(app: &mut App) {
+  app.register_type::<Jump>().add_systems(Update, build);
+}
+
+/// The highest jump considered while building the graph, in tiles
+const MAX_JUMP: Jump = Jump { height: 4, distance: 6 };
+
+/// Jump capability of an agent, in tiles
+#[derive(Reflect, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct Jump {
+  pub height: i32,
+  pub distance: i32,
+}
+
+impl Jump {
+  pub const NONE: Self = Self { height: 0, distance: 0 };
+
+  fn reaches(&self, height: i32, distance: i32) -> bool {
+    height <= self.height && distance <= self.distance
+  }
+}
+
+impl Default for Jump {
+  fn default() -> Self {
+    Self { height: 2, distance: 3 }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Link {
+  Walk,
+  Fall,
+  Jump { height: i32, distance: i32 },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Edge {
+  pub to: IVec2,
+  pub link: Link,
+  cost: u32,
+}
+
+/// Walkable surface of the level: an empty cell right above a wall.
+///
+/// Stored on the level entity, so it is despawned with it.
+#[derive(Component, Default, Debug)]
+pub struct NavGraph {
+  pub size: IVec2,
+  pub grid_size: f32,
+  nodes: HashMap<IVec2, Vec<Edge>>,
+}
+
+impl NavGraph {
+  pub fn new(solid: &HashSet<IVec2>, size: IVec2, grid_size: f32) -> Self {
+    let inside =
+      |cell: IVec2| cell.cmpge(IVec2::ZERO).all() && cell.cmplt(size).all();
+    let empty = |cell: IVec2| inside(cell) && !solid.contains(&cell);
+    let walkable =
+      |cell: IVec2| empty(cell) && solid.contains(&(cell - IVec2::Y));
+
+    let mut nodes = HashMap::new();
+    for y in 0..size.y {
+      for x in 0..size.x {
+        let from = IVec2::new(x, y);
+        if !walkable(from) {
+          continue;
+        }
+
+        let mut edges = Vec::new();
+        for dx in [-1, 1] {
+          let side = from + IVec2::X * dx;
+          if walkable(side) {
+            edges.push(Edge { to: side, link: Link::Walk, cost: 1 });
+          } else if empty(side) {
+            // step off the edge and fall down to the first surface
+            let mut to = side;
+            while empty(to - IVec2::Y) {
+              to -= IVec2::Y;
+            }
+            if walkable(to) {
+              let cost = 1 + (from.y - to.y) as u32;
+              edges.push(Edge { to, link: Link::Fall, cost });
+            }
+          }
+        }
+
+        for dy in -MAX_JUMP.height..=MAX_JUMP.height {
+          for dx in -MAX_JUMP.distance..=MAX_JUMP.distance {
+            let to = from + IVec2::new(dx, dy);
+            if dx.abs() <= 1 && dy <= 0 || !walkable(to) {
+              // already covered by walking or falling
+              continue;
+            }
+
+            // rise, fly over and land, every cell of the arc must be empty
+            let top = from.y.max(to.y);
+            let clear = (from.y..=top).all(|y| empty(IVec2::new(from.x, y)))
+              && (from.x.min(to.x)..=from.x.max(to.x))
+                .all(|x| empty(IVec2::new(x, top)))
+              && (to.y..=top).all(|y| empty(IVec2::new(to.x, y)));
+
+            if clear {
+              let link = Link::Jump { height: dy.max(1), distance: dx.abs() };
+              // prefer walking over jumping the same distance
+              let cost = 2 * (dx.abs() + dy.abs()) as u32 + 1;
+              edges.push(Edge { to, link, cost });
+            }
+          }
+        }
+
+        nodes.insert(from, edges);
+      }
+    }
+
+    Self { size, grid_size, nodes }
+  }
+
+  pub fn contains(&self, cell: IVec2) -> bool {
+    self.nodes.contains_key(&cell)
+  }
+
+  pub fn edges(&self, cell: IVec2) -> &[Edge] {
+    self.nodes.get(&cell).map(Vec::as_slice).unwrap_or_default()
+  }
+
+  /// The first node at or below the `cell`
+  pub fn ground(&self, cell: IVec2) -> Option<IVec2> {
+    (0..=cell.y)
+      .rev()
+      .map(|y| IVec2::new(cell.x, y))
+      .find(|&cell| self.contains(cell))
+  }
+
+  /// A* search, `jump` filters out the jump edges the agent can't perform.
+  ///
+  /// Returns every step of the path with the link used to reach it,
+  /// the `from` cell itself is not included.
+  pub fn path(
+    &self,
+    from: IVec2,
+    to: IVec2,
+    jump: Jump,
+  ) -> Option<Vec<(IVec2, Link)>> {
+    if !self.contains(from) || !self.contains(to) {
+      return None;
+    }
+
+    let heuristic = |cell: IVec2| (to - cell).abs().element_sum() as u32;
+
+    let mut open =
+      BinaryHeap::from([Reverse((heuristic(from), from.to_array()))]);
+    let mut came: HashMap<IVec2, (IVec2, Link)> = HashMap::new();
+    let mut cost: HashMap<IVec2, u32> = HashMap::from([(from, 0)]);
+
+    while let Some(Reverse((_, cell))) = open.pop() {
+      let cell = IVec2::from_array(cell);
+      if cell == to {
+        let mut path = vec![];
+        let mut cell = to;
+        while let Some(&(prev, link)) = came.get(&cell) {
+          path.push((cell, link));
+          cell = prev;
+        }
+        path.reverse();
+        return Some(path);
+      }
+
+      for edge in self.edges(cell) {
+        if let Link::Jump { height, distance } = edge.link
+          && !jump.reaches(height, distance)
+        {
+          continue;
+        }
+
+        let next = cost[&cell] + edge.cost;
+        if cost.get(&edge.to).is_none_or(|&prev| next < prev) {
+          cost.insert(edge.to, next);
+          came.insert(edge.to, (cell, edge.link));
+          open.push(Reverse((next + heuristic(edge.to), edge.to.to_array())));
+        }
+      }
+    }
+    None
+  }
+}
+
+/// Point of the path in world space
+#[derive(Clone, Copy, Debug)]
+pub struct Waypoint {
+  pub position: Vec2,
+  pub link: Link,
+}
+
+/// Path queries across all spawned levels.
+#[derive(SystemParam)]
+pub struct Navigation<'w, 's> {
+  levels: Query<'w, 's, (&'static NavGraph, &'static Transform2D)>,
+}
+
+impl Navigation<'_, '_> {
+  /// Level graph under the `position` and the cell in it
+  pub fn locate(&self, position: Vec2) -> Option<(&NavGraph, Vec2, IVec2)> {
+    self.levels.iter().find_map(|(graph, transform)| {
+      let origin = transform.translation;
+      let cell = ((position - origin) / graph.grid_size).floor().as_ivec2();
+      (cell.cmpge(IVec2::ZERO).all() && cell.cmplt(graph.size).all())
+        .then_some((graph, origin, cell))
+    })
+  }
+
+  /// Path between two points of the same level
+  pub fn path(
+    &self,
+    from: Vec2,
+    to: Vec2,
+    jump: Jump,
+  ) -> Option<Vec<Waypoint>> {
+    let (graph, origin, start) = self.locate(from)?;
+    let goal = ((to - origin) / graph.grid_size).floor().as_ivec2();
+
+    let path = graph.path(graph.ground(start)?, graph.ground(goal)?, jump)?;
+    Some(
+      path
+        .into_iter()
+        .map(|(cell, link)| Waypoint {
+          position: origin + (cell.as_vec2() + 0.5) * graph.grid_size,
+          link,
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Rebuilds the graph of every level whose walls have changed
+fn build(
+  mut commands: Commands,
+  grids: Query<(&ChildOf, &LayerMetadata, Ref<WallGrid>)>,
+) {
+  let changed: HashSet<Entity> = grids
+    .iter()
+    .filter(|(.., grid)| grid.is_changed())
+    .map(|(child_of, ..)| child_of.parent())
+    .collect();
+
+  for level in changed {
+    // layer -> level
+    let layers = grids
+      .iter()
+      .filter(|(child_of, ..)| child_of.parent() == level)
+      .map(|(_, layer, grid)| {
+        let solid = grid
+          .cells
+          .iter()
+          .filter(|(_, tile)| tile.is_solid())
+          .map(|(&GridCoords { x, y }, _)| IVec2::new(x, y))
+          .collect();
+        (layer, solid)
+      })
+      .collect();
+
+    if let Some((solid, layer)) = merge_layers(layers) {
+      let size = IVec2::new(layer.c_wid, layer.c_hei);
+      commands.entity(level).insert(NavGraph::new(
+        &solid,
+        size,
+        layer.grid_size as f32,
+      ));
+    }
+  }
+}
+
+/// Solid cells of the layers sharing the grid of the one with the most
+/// walls, along with that layer.
+///
+/// Layers of another grid size can't be merged into its cells, so they are
+/// left out of the graph.
+fn merge_layers(
+  layers: Vec<(&LayerMetadata, HashSet<IVec2>)>,
+) -> Option<(HashSet<IVec2>, &LayerMetadata)> {
+  let &(main, _) = layers.iter().max_by_key(|(_, solid)| solid.len())?;
+
+  let solid = layers
+    .into_iter()
+    .filter(|(layer, _)| layer.grid_size == main.grid_size)
+    .flat_map(|(_, solid)| solid)
+    .collect();
+  Some((solid, main))
+}
+
+#[test]
+fn path() {
+  // ##.....
+  // ##...##
+  // #######
+  let solid = (0..7)
+    .map(|x| (x, 0))
+    .chain([(0, 1), (1, 1), (5, 1), (6, 1), (0, 2), (1, 2)])
+    .map(IVec2::from)
+    .collect();
+  let graph = NavGraph::new(&solid, IVec2::new(7, 3), 16.0);
+
+  let from = IVec2::new(2, 1);
+  let to = IVec2::new(5, 2);
+
+  assert_eq!(graph.path(from, to, Jump::NONE), None);
+
+  let path = graph.path(from, to, Jump::default()).unwrap();
+  assert_eq!(path.last(), Some(&(to, Link::Jump { height: 1, distance: 1 })));
+  // and back by falling down
+  let path = graph.path(to, from, Jump::NONE).unwrap();
+  assert!(path.iter().any(|&(_, link)| link == Link::Fall));
+}
+
+#[test]
+fn layer_grids() {
+  let layer = |grid_size, size: i32| LayerMetadata {
+    grid_size,
+    c_wid: size,
+    c_hei: size,
+    ..default()
+  };
+  let cells = |cells: &[(i32, i32)]| -> HashSet<IVec2> {
+    cells.iter().copied().map(IVec2::from).collect()
+  };
+  let (terrain, platforms, details) = (layer(16, 4), layer(16, 4), layer(8, 8));
+
+  let (solid, main) = merge_layers(vec![
+    (&details, cells(&[(2, 1)])),
+    (&terrain, cells(&[(0, 0), (1, 0), (2, 0), (3, 0)])),
+    (&platforms, cells(&[(3, 2)])),
+  ])
+  .unwrap();
+  assert_eq!(main.grid_size, 16);
+  assert_eq!(solid, cells(&[(0, 0), (1, 0), (2, 0), (3, 0), (3, 2)]));
+
+  let graph = NavGraph::new(
+    &solid,
+    IVec2::new(main.c_wid, main.c_hei),
+    main.grid_size as f32,
+  );
+  assert_eq!(graph.grid_size, 16.0);
+  assert!(graph.contains(IVec2::new(3, 3)));
+  // the cell of the finer layer isn't a wall of this grid
+  assert!(graph.contains(IVec2::new(2, 1)));
+}
@@ -0,0 +1,118 @@
+//! Runtime changes of LDtk entities that survive level respawns.
+//!
+//! `bevy_ecs_ldtk` rebuilds entities from the project file every time their
+//! level is spawned, so any opted-in component is recorded by the entity iid
+//! and put back when an entity with the same iid appears again.
+
+use {super::ldtk::MapFields, crate::prelude::*};
+
+pub trait RegisterPersistent {
+  fn register_persistent<C: Persistent>(&mut self) -> &mut Self;
+}
+
+impl RegisterPersistent for App {
+  fn register_persistent<C: Persistent>(&mut self) -> &mut Self {
+    self
+      .init_resource::<WorldState<C>>()
+      .add_systems(
+        Update,
+        (
+          apply::<C>.after(MapFields).before(Systems::Spawn),
+          record::<C>.in_set(Systems::Watch),
+        ),
+      )
+      .add_systems(OnExit(Game::Gameplay), clear::<C>)
+  }
+}
+
+pub trait Persistent: Component<Mutability = Mutable> + Clone {}
+
+impl<C: Component<Mutability = Mutable> + Clone> Persistent for C {}
+
+/// Last known state of the `C` component of every changed LDtk entity.
+#[derive(Resource)]
+pub struct WorldState<C> {
+  overrides: HashMap<String, C>,
+}
+
+impl<C> Default for WorldState<C> {
+  fn default() -> Self {
+    Self { overrides: HashMap::new() }
+  }
+}
+
+impl<C> WorldState<C> {
+  pub fn get(&self, iid: &EntityIid) -> Option<&C> {
+    self.overrides.get(&iid.to_string())
+  }
+}
+
+fn apply<C: Persistent>(
+  state: Res<WorldState<C>>,
+  mut query: Query<(&EntityIid, &mut C), Added<C>>,
+) {
+  for (iid, mut component) in query.iter_mut() {
+    if let Some(saved) = state.get(iid) {
+      *component = saved.clone();
+    }
+  }
+}
+
+fn record<C: Persistent>(
+  mut state: ResMut<WorldState<C>>,
+  query: Query<(&EntityIid, Ref<C>), Changed<C>>,
+) {
+  for (iid, component) in query.iter() {
+    // fresh entities are either in the project file or already recorded
+    if !component.is_added() {
+      state.overrides.insert(iid.to_string(), component.clone());
+    }
+  }
+}
+
+fn clear<C: Persistent>(mut state: ResMut<WorldState<C>>) {
+  state.overrides.clear();
+}
+
+#[test]
+fn record_then_respawn() {
+  use bevy::state::app::StatesPlugin;
+
+  #[derive(Component, Clone, Debug, PartialEq)]
+  struct Lever(bool);
+
+  let mut app = App::new();
+  app
+    .add_plugins(StatesPlugin)
+    .init_state::<Game>()
+    .register_persistent::<Lever>();
+  let iid = EntityIid::new("lever");
+  // the level spawns the entity as it is in the project file
+  let spawn =
+    |app: &mut App| app.world_mut().spawn((iid.clone(), Lever(false))).id();
+  let recorded =
+    |app: &App| app.world().resource::<WorldState<Lever>>().get(&iid).cloned();
+  let set = |app: &mut App, game| {
+    app.world_mut().resource_mut::<NextState<Game>>().set(game);
+    app.update();
+  };
+
+  set(&mut app, Game::Gameplay);
+  let lever = spawn(&mut app);
+  app.update();
+  // fresh entities are not recorded
+  assert_eq!(recorded(&app), None);
+
+  app.world_mut().get_mut::<Lever>(lever).unwrap().0 = true;
+  app.update();
+  assert_eq!(recorded(&app), Some(Lever(true)));
+
+  app.world_mut().despawn(lever);
+  let lever = spawn(&mut app);
+  app.update();
+  assert_eq!(app.world().get::<Lever>(lever), Some(&Lever(true)));
+
+  // a new game starts from the project file
+  set(&mut app, Game::Title);
+  assert_eq!(recorded(&app), None);
+}
@@ -6,6 +6,7 @@ use crate::prelude::*;
 mod actors;
 mod core;
 mod level;
+mod save;
 mod ui;
 //
 mod assets;
@@ -22,7 +23,7 @@ impl Plugin for GamePlugin {
 
     app.add_plugins(StateMachinePlugin::default());
 
-    app.add_plugins((ui::plugin, level::plugin, actors::plugin));
+    app.add_plugins((ui::plugin, level::plugin, actors::plugin, save::plugin));
     app.add_systems(Startup, spawn_camera);
   }
 }
@@ -0,0 +1,145 @@
+//! Save slots, written on every checkpoint, progress change and on quit.
+
+use {
+  crate::{
+    actors::{Player, item::Item},
+    level::checkpoint::SpawnPoint,
+    prelude::*,
+  },
+  bevy::ecs::schedule::common_conditions::on_message,
+  serde::{Deserialize, Serialize},
+  std::{collections::BTreeSet, fs, path::PathBuf},
+};
+
+pub fn plugin(app: &mut App) {
+  app
+    .register_type::<Progress>()
+    .add_systems(OnEnter(Game::Gameplay), load)
+    .add_systems(
+      Update,
+      (
+        restore.in_set(Systems::Spawn).run_if(
+          resource_exists::<Restore>.and(resource_exists::<SpawnPoint>),
+        ),
+        save.in_set(Systems::Watch).run_if(
+          resource_exists_and_changed::<SpawnPoint>
+            .or(resource_exists_and_changed::<Progress>),
+        ),
+      ),
+    )
+    .add_systems(OnExit(Game::Gameplay), (save, cleanup).chain())
+    // quitting from the gameplay skips its `OnExit`
+    .add_systems(Last, save.run_if(on_message::<AppExit>));
+}
+
+pub const SLOTS: usize = 3;
+
+const SAVE_DIR: &str = "saves";
+
+/// The slot the current game is saved to
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Slot(pub usize);
+
+/// Progress of the game besides the spawn point.
+///
+/// Entities placed in LDtk are identified by their `iid`,
+/// so they stay opened, killed or broken after reload.
+#[derive(Resource, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Progress {
+  pub inventory: Vec<Item>,
+  /// opened chests
+  pub opened: BTreeSet<String>,
+  /// killed unique enemies
+  pub killed: BTreeSet<String>,
+  /// broken bricks
+  pub broken: BTreeSet<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Spawn {
+  pub level: String,
+  pub position: (f32, f32),
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct SaveData {
+  /// the last checkpoint, start of the game otherwise
+  pub spawn: Option<Spawn>,
+  pub progress: Progress,
+}
+
+impl SaveData {
+  fn path(slot: usize) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(format!("slot{slot}.ron"))
+  }
+
+  /// `None` for an empty or unreadable slot
+  pub fn read(slot: usize) -> Option<Self> {
+    let bytes = fs::read(Self::path(slot)).ok()?;
+    ron::de::from_bytes(&bytes)
+      .inspect_err(|err| warn!("broken save slot {slot}: {err}"))
+      .ok()
+  }
+
+  pub fn write(&self, slot: usize) -> Result {
+    let data = ron::ser::to_string_pretty(self, default())?;
+    fs::create_dir_all(SAVE_DIR)?;
+    fs::write(Self::path(slot), data)?;
+    Ok(())
+  }
+}
+
+/// The player must be moved to the loaded spawn point
+#[derive(Resource)]
+struct Restore;
+
+fn load(mut commands: Commands, slot: Option<Res<Slot>>) {
+  let data = slot.and_then(|slot| SaveData::read(slot.0)).unwrap_or_default();
+
+  if let Some(Spawn { level, position }) = data.spawn {
+    commands.insert_resource(SpawnPoint { level, position: position.into() });
+    commands.insert_resource(Restore);
+  }
+  commands.insert_resource(data.progress);
+}
+
+fn restore(
+  mut commands: Commands,
+  spawn_point: Res<SpawnPoint>,
+  player: Single<(&mut Transform2D, Option<&ChildOf>), With<Player>>,
+  parents: Query<&GlobalTransform2D>,
+  mut selection: ResMut<LevelSelection>,
+) {
+  let (mut transform, child_of) = player.into_inner();
+  transform.translation =
+    world_to_local(spawn_point.position, child_of, &parents);
+  *selection = LevelSelection::iid(spawn_point.level.clone());
+  commands.remove_resource::<Restore>();
+}
+
+fn save(
+  slot: Option<Res<Slot>>,
+  progress: Option<Res<Progress>>,
+  spawn_point: Option<Res<SpawnPoint>>,
+) {
+  let (Some(slot), Some(progress)) = (slot, progress) else { return };
+
+  let data = SaveData {
+    spawn: spawn_point.map(|spawn| Spawn {
+      level: spawn.level.clone(),
+      position: spawn.position.into(),
+    }),
+    progress: progress.clone(),
+  };
+  if let Err(err) = data.write(slot.0) {
+    error!("failed to save slot {}: {err}", slot.0);
+  }
+}
+
+fn cleanup(mut commands: Commands) {
+  commands.remove_resource::<Progress>();
+  commands.remove_resource::<Restore>();
+}
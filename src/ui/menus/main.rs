@@ -1,6 +1,6 @@
 use crate::{
   prelude::*,
-  ui::{Game, Menu, widget},
+  ui::{Menu, widget},
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -14,7 +14,7 @@ fn spawn_main_menu(mut commands: Commands) {
     DespawnOnExit(Menu::Main),
     #[cfg(not(target_family = "wasm"))]
     children![
-      widget::button("Play", enter_gameplay),
+      widget::button("Play", open_slots_menu),
       widget::button("Settings", open_settings_menu),
       widget::button("Credits", open_credits_menu),
       widget::button("Exit", exit_app),
@@ -28,11 +28,11 @@ fn spawn_main_menu(mut commands: Commands) {
   ));
 }
 
-fn enter_gameplay(
+fn open_slots_menu(
   _: Trigger<Pointer<Click>>,
-  mut next_screen: ResMut<NextState<Game>>,
+  mut next_menu: ResMut<NextState<Menu>>,
 ) {
-  next_screen.set(Game::Gameplay);
+  next_menu.set(Menu::Slots);
 }
 
 fn open_settings_menu(
@@ -19,9 +19,11 @@ pub(super) fn plugin(app: &mut App) {
   );
 
   app.register_type::<GlobalVolumeLabel>();
+  app.register_type::<GameSpeedLabel>();
   app.add_systems(
     Update,
-    update_global_volume_label.run_if(in_state(Menu::Settings)),
+    (update_global_volume_label, update_game_speed_label)
+      .run_if(in_state(Menu::Settings)),
   );
 }
 
@@ -54,6 +56,11 @@ fn settings_grid() -> impl Bundle {
         Node { justify_self: JustifySelf::End, ..default() }
       ),
       global_volume_widget(),
+      (
+        widget::label("Game Speed"),
+        Node { justify_self: JustifySelf::End, ..default() }
+      ),
+      game_speed_widget(),
     ],
   )
 }
@@ -109,6 +116,56 @@ fn update_global_volume_label(
   label.0 = format!("{percent:3.0}%");
 }
 
+fn game_speed_widget() -> impl Bundle {
+  (
+    Name::new("Game Speed Widget"),
+    Node { justify_self: JustifySelf::Start, ..default() },
+    children![
+      widget::button_small("-", lower_game_speed),
+      (
+        Name::new("Current Game Speed"),
+        Node {
+          padding: UiRect::horizontal(Px(10.0)),
+          justify_content: JustifyContent::Center,
+          ..default()
+        },
+        children![(widget::label(""), GameSpeedLabel)],
+      ),
+      widget::button_small("+", raise_game_speed),
+    ],
+  )
+}
+
+/// Slower game for the players who need more time to react
+const MIN_GAME_SPEED: f32 = 0.5;
+const MAX_GAME_SPEED: f32 = 1.0;
+
+fn lower_game_speed(
+  _: Trigger<Pointer<Click>>,
+  mut time_scale: ResMut<TimeScale>,
+) {
+  time_scale.base = (time_scale.base - 0.1).max(MIN_GAME_SPEED);
+}
+
+fn raise_game_speed(
+  _: Trigger<Pointer<Click>>,
+  mut time_scale: ResMut<TimeScale>,
+) {
+  time_scale.base = (time_scale.base + 0.1).min(MAX_GAME_SPEED);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GameSpeedLabel;
+
+fn update_game_speed_label(
+  time_scale: Res<TimeScale>,
+  mut label: Single<&mut Text, With<GameSpeedLabel>>,
+) {
+  let percent = 100.0 * time_scale.base;
+  label.0 = format!("{percent:3.0}%");
+}
+
 fn go_back_on_click(
   _: Trigger<Pointer<Click>>,
   screen: Res<State<Game>>,
@@ -0,0 +1,62 @@
+//! The save slot picker, continues a saved game or starts a new one.
+
+use bevy::input::common_conditions::input_just_pressed;
+
+use crate::{
+  prelude::*,
+  save::{SLOTS, SaveData, Slot},
+  ui::{Menu, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+  app.add_systems(OnEnter(Menu::Slots), spawn_slots_menu);
+  app.add_systems(
+    Update,
+    go_back
+      .run_if(in_state(Menu::Slots).and(input_just_pressed(KeyCode::Escape))),
+  );
+}
+
+fn spawn_slots_menu(mut commands: Commands) {
+  commands
+    .spawn((
+      widget::ui_root("Slots Menu"),
+      GlobalZIndex(2),
+      DespawnOnExit(Menu::Slots),
+      children![widget::header("Save Slots")],
+    ))
+    .with_children(|parent| {
+      for slot in 0..SLOTS {
+        parent.spawn(widget::button(
+          slot_label(slot),
+          move |_: Trigger<Pointer<Click>>,
+                mut commands: Commands,
+                mut next_screen: ResMut<NextState<Game>>| {
+            commands.insert_resource(Slot(slot));
+            next_screen.set(Game::Gameplay);
+          },
+        ));
+      }
+      parent.spawn(widget::button("Back", go_back_on_click));
+    });
+}
+
+fn slot_label(slot: usize) -> String {
+  match SaveData::read(slot) {
+    Some(data) => {
+      format!("Continue {} ({} items)", slot + 1, data.progress.inventory.len())
+    }
+    None => format!("New Game {}", slot + 1),
+  }
+}
+
+fn go_back_on_click(
+  _: Trigger<Pointer<Click>>,
+  mut next_menu: ResMut<NextState<Menu>>,
+) {
+  next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+  next_menu.set(Menu::Main);
+}